@@ -0,0 +1,47 @@
+//! Generates a test for every example fixture, see `src/examples.rs`.
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// `dayNN.example.txt` or `dayNN.example.N.txt`, the same rules as `examples::fixture_day`
+fn test_name(file_name: &str) -> Option<String> {
+    let rest = file_name.strip_prefix("day")?;
+    let (day, rest) = rest.split_at_checked(2)?;
+    let index = rest.strip_prefix(".example")?.strip_suffix(".txt")?;
+    if !day.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    return match index.strip_prefix('.') {
+        Some(i) if !i.is_empty() && i.chars().all(|c| c.is_ascii_digit()) => {
+            Some(format!("day{}_example_{}", day, i))
+        }
+        None if index.is_empty() => Some(format!("day{}_example", day)),
+        _ => None,
+    };
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+
+    let mut fixtures: Vec<(String, String)> = fs::read_dir("src")
+        .expect("src is readable")
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let file_name = entry.file_name().into_string().ok()?;
+            test_name(&file_name).map(|test| (test, file_name))
+        })
+        .collect();
+    fixtures.sort();
+
+    let tests: String = fixtures
+        .iter()
+        .map(|(test, file_name)| {
+            format!(
+                "#[test]\nfn {}() {{\n    assert_example(\"src/{}\");\n}}\n",
+                test, file_name
+            )
+        })
+        .collect();
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    fs::write(Path::new(&out_dir).join("example_tests.rs"), tests).expect("OUT_DIR is writable");
+}
@@ -0,0 +1,199 @@
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{self, Serialize, Serializer};
+use std::fmt::{self, Display};
+
+/// The answer to a part of a puzzle, puzzles ask either for a number or (rarely) for some text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+    /// The input has no answer to the part (like the example of the other part), and why.
+    Unsolvable(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // forwarded, so width and alignment work for answers too
+            Answer::Number(x) => Display::fmt(x, f),
+            Answer::Text(x) => f.pad(x),
+            Answer::Unsolvable(reason) => f.pad(&format!("no answer, {}", reason)),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Answer {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Answer {
+        Answer::Text(value.to_string())
+    }
+}
+
+impl PartialEq<str> for Answer {
+    fn eq(&self, other: &str) -> bool {
+        match self {
+            Answer::Number(x) => x.to_string() == other,
+            Answer::Text(x) => x == other,
+            Answer::Unsolvable(_) => false,
+        }
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl PartialEq<Answer> for &str {
+    fn eq(&self, other: &Answer) -> bool {
+        other == *self
+    }
+}
+
+impl PartialEq<String> for Answer {
+    fn eq(&self, other: &String) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<Answer> for String {
+    fn eq(&self, other: &Answer) -> bool {
+        other == self.as_str()
+    }
+}
+
+/// numbers convert into (and compare with) [`Answer::Number`], whatever their width
+macro_rules! number_answer {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Answer {
+            fn from(value: $t) -> Answer {
+                Answer::Number(i128::try_from(value).expect("answers fit into an i128"))
+            }
+        }
+
+        impl PartialEq<$t> for Answer {
+            fn eq(&self, other: &$t) -> bool {
+                match (self, i128::try_from(*other)) {
+                    (Answer::Number(x), Ok(y)) => *x == y,
+                    _ => false,
+                }
+            }
+        }
+
+        impl PartialEq<Answer> for $t {
+            fn eq(&self, other: &Answer) -> bool {
+                other == self
+            }
+        }
+    )*};
+}
+
+number_answer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            // not every format has 128 bit numbers, the bigger ones are written as strings
+            Answer::Number(x) => match i64::try_from(*x) {
+                Ok(x) => serializer.serialize_i64(x),
+                Err(_) => serializer.serialize_str(&x.to_string()),
+            },
+            Answer::Text(x) => serializer.serialize_str(x),
+            Answer::Unsolvable(reason) => Err(ser::Error::custom(format!(
+                "there is no answer to save, {}",
+                reason
+            ))),
+        }
+    }
+}
+
+struct AnswerVisitor;
+
+impl Visitor<'_> for AnswerVisitor {
+    type Value = Answer;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a number or a string")
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Answer, E> {
+        Ok(value.into())
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Answer, E> {
+        Ok(value.into())
+    }
+
+    fn visit_i128<E: de::Error>(self, value: i128) -> Result<Answer, E> {
+        Ok(value.into())
+    }
+
+    fn visit_u128<E: de::Error>(self, value: u128) -> Result<Answer, E> {
+        i128::try_from(value)
+            .map(Answer::Number)
+            .map_err(|_| E::custom("answer does not fit into an i128"))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Answer, E> {
+        match value.parse::<i128>() {
+            Ok(x) => Ok(Answer::Number(x)),
+            Err(_) => Ok(value.into()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Answer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Answer, D::Error> {
+        deserializer.deserialize_any(AnswerVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equality() {
+        assert_eq!(Answer::from(55621_i128), 55621);
+        assert_eq!(637087163925555_u64, Answer::from(637087163925555_usize));
+        assert_eq!(Answer::from(-3), "-3");
+        assert_eq!(Answer::from("ABC"), "ABC");
+        assert_ne!(Answer::from(-1), u32::MAX);
+        assert_ne!(Answer::from("1"), 1);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            Answer::from(u128::from(u64::MAX)).to_string(),
+            "18446744073709551615"
+        );
+        assert_eq!(Answer::from("LJ7F").to_string(), "LJ7F");
+        let unsolvable = Answer::Unsolvable("there is no node 'AAA'".to_string());
+        assert_eq!(unsolvable.to_string(), "no answer, there is no node 'AAA'");
+        assert_ne!(unsolvable, "there is no node 'AAA'");
+    }
+
+    #[test]
+    fn serialization() {
+        let answers = vec![
+            Answer::from(42),
+            Answer::from(i128::MAX),
+            Answer::from("text"),
+        ];
+        let json = serde_json::to_string(&answers).unwrap();
+        assert_eq!(
+            json,
+            r#"[42,"170141183460469231731687303715884105727","text"]"#
+        );
+        let parsed: Vec<Answer> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, answers);
+        assert!(serde_json::to_string(&Answer::Unsolvable("why".to_string())).is_err());
+    }
+}
@@ -0,0 +1,169 @@
+//! Wall time of the solvers, for `aoc-2023 bench`.
+//!
+//! Every step (parsing and both parts) is run a number of times and summarized as min/median/max.
+//! The medians can be saved as a baseline, later runs flag the steps that got slower than that.
+use crate::error::{Message, ParseError};
+use crate::solution::Solver;
+use std::collections::BTreeMap;
+use std::fs;
+use std::hint::black_box;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+pub const STEPS: [&str; 3] = ["parse", "part1", "part2"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timing {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Timing {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Timing {
+        assert!(!samples.is_empty(), "no samples");
+        samples.sort();
+        let mid = samples.len() / 2;
+        let median = match samples.len() % 2 {
+            0 => (samples[mid - 1] + samples[mid]) / 2,
+            _ => samples[mid],
+        };
+        return Timing {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        };
+    }
+}
+
+/// Runs `f` `runs` times (at least once).
+pub fn measure<T, F: FnMut() -> T>(runs: usize, mut f: F) -> Timing {
+    let samples = (0..runs.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect();
+    return Timing::from_samples(samples);
+}
+
+/// The timings of the [`STEPS`] of a day, in that order.
+pub fn bench_day(
+    solver: &dyn Solver,
+    input: &str,
+    runs: usize,
+) -> Result<[Timing; 3], ParseError<Message>> {
+    let prepared = solver.prepare(input)?;
+    let parse = measure(runs, || solver.prepare(input).is_ok());
+    let part1 = measure(runs, || prepared.part1());
+    let part2 = measure(runs, || prepared.part2());
+    return Ok([parse, part1, part2]);
+}
+
+/// Median times in nanoseconds, keyed by [`baseline_key`].
+pub type Baseline = BTreeMap<String, u64>;
+
+pub fn baseline_key(day: u8, step: &str) -> String {
+    format!("day{:02}/{}", day, step)
+}
+
+#[derive(Debug, Error)]
+pub enum BaselineError {
+    #[error("could not access the baseline: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid baseline: {0}")]
+    Format(#[from] serde_json::Error),
+}
+
+pub fn load_baseline(path: &Path) -> Result<Baseline, BaselineError> {
+    let text = fs::read_to_string(path)?;
+    return Ok(serde_json::from_str(&text)?);
+}
+
+/// Merges `medians` into the baseline at `path`, the entries of days that weren't benched are kept.
+pub fn save_baseline(path: &Path, medians: &Baseline) -> Result<(), BaselineError> {
+    let mut baseline = match load_baseline(path) {
+        Ok(x) => x,
+        Err(BaselineError::Io(e)) if e.kind() == io::ErrorKind::NotFound => Baseline::new(),
+        Err(e) => return Err(e),
+    };
+    baseline.extend(medians.iter().map(|(key, &nanos)| (key.clone(), nanos)));
+    let text = serde_json::to_string_pretty(&baseline)?;
+    fs::write(path, text + "\n")?;
+    return Ok(());
+}
+
+/// How much slower `current` got compared to `baseline` (0.5 is 50% slower), when that's more than `threshold`.
+pub fn regression(baseline: Duration, current: Duration, threshold: f64) -> Option<f64> {
+    if baseline.is_zero() {
+        return None;
+    }
+    let slowdown = current.as_secs_f64() / baseline.as_secs_f64() - 1.0;
+    return (slowdown > threshold).then_some(slowdown);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(x: u64) -> Duration {
+        Duration::from_millis(x)
+    }
+
+    #[test]
+    fn timing_from_samples() {
+        let odd = Timing::from_samples(vec![ms(5), ms(1), ms(3)]);
+        assert_eq!(
+            odd,
+            Timing {
+                min: ms(1),
+                median: ms(3),
+                max: ms(5)
+            }
+        );
+        let even = Timing::from_samples(vec![ms(4), ms(1), ms(2), ms(9)]);
+        assert_eq!(
+            even,
+            Timing {
+                min: ms(1),
+                median: ms(3),
+                max: ms(9)
+            }
+        );
+    }
+
+    #[test]
+    fn regressions() {
+        let secs = Duration::from_secs;
+        assert_eq!(regression(secs(2), secs(3), 0.2), Some(0.5));
+        assert_eq!(regression(ms(100), ms(110), 0.2), None);
+        assert_eq!(regression(ms(100), ms(50), 0.2), None);
+        assert_eq!(regression(ms(0), ms(50), 0.2), None);
+    }
+
+    #[test]
+    fn save_baseline_merges() {
+        let path = std::env::temp_dir().join(format!("aoc-baseline-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let entry = |day: u8, nanos: u64| (baseline_key(day, "part1"), nanos);
+        save_baseline(&path, &Baseline::from([entry(1, 10)])).unwrap();
+        save_baseline(&path, &Baseline::from([entry(6, 20)])).unwrap();
+        save_baseline(&path, &Baseline::from([entry(1, 30)])).unwrap();
+        let saved = load_baseline(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(saved, Baseline::from([entry(1, 30), entry(6, 20)]));
+    }
+
+    #[test]
+    fn bench_a_day() {
+        let solver = crate::solution::solver(1).unwrap();
+        let timings = bench_day(solver, "1abc2\ntreb7uchet", 3).unwrap();
+        assert!(timings
+            .iter()
+            .all(|t| t.min <= t.median && t.median <= t.max));
+        assert!(bench_day(solver, "abc", 3).is_err());
+    }
+}
@@ -1,46 +1,94 @@
-fn solve<F>(input: &str, parse_digit: F) -> i128
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
+use crate::utils::parse;
+use regex::Regex;
+use thiserror::Error;
+
+const SPELLED_DIGITS: &str = "one|two|three|four|five|six|seven|eight|nine";
+
+pub struct Day01;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("no digit in calibration line")]
+    NoDigit,
+}
+
+impl Hint for ErrorKind {
+    fn hint(&self) -> Option<String> {
+        Some(
+            "calibration lines have a digit or a spelled out one, like 'a1b2c3' or 'two1nine'"
+                .to_string(),
+        )
+    }
+}
+
+fn solve<F>(lines: &[String], parse_digit: F) -> i128
 where
     F: Fn(usize, char, &str) -> Option<u32>,
 {
-    let numbers = input.split("\n").map(|line| {
+    // a line can have only spelled out digits, those don't count when they aren't parsed
+    let numbers = lines.iter().filter_map(|line| {
         let digits: Vec<i128> = line
             .char_indices()
             .filter_map(|(i, c)| parse_digit(i, c, line))
             .map(i128::from)
             .collect();
 
-        return digits.first().unwrap() * 10 + digits.last().unwrap();
+        return match (digits.first(), digits.last()) {
+            (Some(first), Some(last)) => Some(first * 10 + last),
+            _ => None,
+        };
     });
 
     return numbers.sum();
 }
 
-pub fn part1(input: &str) -> i128 {
-    return solve(input, |_, c, _| c.to_digit(10));
-}
+impl Solution for Day01 {
+    type Parsed = Vec<String>;
+    type ErrorKind = ErrorKind;
 
-use regex::Regex;
-pub fn part2(input: &str) -> i128 {
-    let numb_re = Regex::new(r"one|two|three|four|five|six|seven|eight|nine").unwrap();
-    return solve(input, |i, c, line| {
-        c.to_digit(10).or_else(|| {
-            numb_re
-                .find_at(line, i)
-                .and_then(|m| match (m.start(), m.as_str()) {
-                    (pos, _) if pos != i => None,
-                    (_, "one") => Some(1),
-                    (_, "two") => Some(2),
-                    (_, "three") => Some(3),
-                    (_, "four") => Some(4),
-                    (_, "five") => Some(5),
-                    (_, "six") => Some(6),
-                    (_, "seven") => Some(7),
-                    (_, "eight") => Some(8),
-                    (_, "nine") => Some(9),
-                    _ => None,
-                })
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        let mut lines = vec![];
+        for line in parse::lines(input) {
+            let has_digit = line.text.chars().any(|c| c.is_ascii_digit())
+                || SPELLED_DIGITS.split('|').any(|d| line.text.contains(d));
+            if !has_digit {
+                return Err(ParseError::new(line.line_no, 1, ErrorKind::NoDigit));
+            }
+            lines.push(line.text.to_string());
+        }
+        return Ok(lines);
+    }
+
+    fn part1(lines: &Self::Parsed) -> Answer {
+        return solve(lines, |_, c, _| c.to_digit(10)).into();
+    }
+
+    fn part2(lines: &Self::Parsed) -> Answer {
+        let numb_re = Regex::new(SPELLED_DIGITS).unwrap();
+        return solve(lines, |i, c, line| {
+            c.to_digit(10).or_else(|| {
+                numb_re
+                    .find_at(line, i)
+                    .and_then(|m| match (m.start(), m.as_str()) {
+                        (pos, _) if pos != i => None,
+                        (_, "one") => Some(1),
+                        (_, "two") => Some(2),
+                        (_, "three") => Some(3),
+                        (_, "four") => Some(4),
+                        (_, "five") => Some(5),
+                        (_, "six") => Some(6),
+                        (_, "seven") => Some(7),
+                        (_, "eight") => Some(8),
+                        (_, "nine") => Some(9),
+                        _ => None,
+                    })
+            })
         })
-    });
+        .into();
+    }
 }
 
 #[cfg(test)]
@@ -57,17 +105,23 @@ a1b2c3d4e5f
 treb7uchet
             "#
         .trim();
-        let result = part1(example);
+        let result = Day01::part1(&Day01::parse(example).unwrap());
         assert_eq!(result, 142)
     }
 
     #[test]
     fn part1_result() {
         let input = utils::resource("src/day01.txt");
-        let result = part1(&input);
+        let result = Day01::part1(&Day01::parse(&input).unwrap());
         assert_eq!(result, 55621);
     }
 
+    #[test]
+    fn part1_spelled_only() {
+        let result = Day01::part1(&Day01::parse("oneight\n1abc2").unwrap());
+        assert_eq!(result, 12)
+    }
+
     #[test]
     fn part2_example() {
         let example = r#"
@@ -79,21 +133,30 @@ xtwone3four
 zoneight234
 7pqrstsixteen"#
             .trim();
-        let result = part2(example);
+        let result = Day01::part2(&Day01::parse(example).unwrap());
         assert_eq!(result, 281)
     }
 
     #[test]
     fn part2_gotcha() {
-        let result = part2("oneight");
+        let result = Day01::part2(&Day01::parse("oneight").unwrap());
         assert_eq!(result, 18);
     }
 
     #[test]
     fn part2_result() {
         let input = utils::resource("src/day01.txt");
-        let result = part2(&input);
+        let result = Day01::part2(&Day01::parse(&input).unwrap());
         assert_ne!(result, 53587); // that's the result we get if we don't respect overlapping matches, see gotcha
         assert_eq!(result, 53592);
     }
+
+    #[test]
+    fn parse_error() {
+        let result = Day01::parse("1abc2\n\ntreb7uchet");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(2, 1, ErrorKind::NoDigit))
+        );
+    }
 }
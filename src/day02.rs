@@ -1,66 +1,87 @@
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
+use crate::utils::parse::{self, Span};
 use dict::{Dict, DictIface};
-use regex::Regex;
+use thiserror::Error;
+
+const PART1_CONSTRAINT: &str = "12 red, 13 green, 14 blue";
+const COLORS: [&str; 3] = ["red", "green", "blue"];
+
+pub struct Day02;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("expected 'Game <id>: <hands>'")]
+    MissingGameId,
+    #[error("expected '<count> <color>', got '{0}'")]
+    InvalidCubes(String),
+    #[error("invalid number '{0}'")]
+    InvalidNumber(String),
+    #[error("unknown color '{0}'")]
+    UnknownColor(String),
+}
+
+impl Hint for ErrorKind {
+    fn hint(&self) -> Option<String> {
+        match self {
+            ErrorKind::UnknownColor(_) => {
+                Some("the cubes are 'red', 'green' or 'blue'".to_string())
+            }
+            _ => Some("games look like 'Game 1: 3 blue, 4 red; 1 red, 2 green'".to_string()),
+        }
+    }
+}
 
-struct Game {
+pub struct Game {
     id: i32,
     hands: Vec<Dict<i32>>,
 }
 
-fn parse_hand(hand_str: &str) -> Dict<i32> {
-    let hand_re = Regex::new(r"(?<count>\d+) (?<color>[^ ,;]+)").unwrap();
-    let mut hand = Dict::<i32>::new();
-    for color_cap in hand_re.captures_iter(hand_str) {
-        let color_count: i32 = color_cap
-            .name("count")
-            .expect("no count")
-            .as_str()
-            .parse()
-            .unwrap();
-
-        let color_name = color_cap
-            .name("color")
-            .expect("no color")
-            .as_str()
-            .to_string();
-        hand.add(color_name, color_count);
+fn parse_hand(hand: Span) -> Result<Dict<i32>, ParseError<ErrorKind>> {
+    let mut result = Dict::<i32>::new();
+    for cubes in hand.split(",").map(|x| x.trim()) {
+        let (count, color) = cubes
+            .split_once(" ")
+            .ok_or_else(|| cubes.error(ErrorKind::InvalidCubes(cubes.text.to_string())))?;
+        let color_count: i32 = count.parse(|x| ErrorKind::InvalidNumber(x.to_string()))?;
+        if !COLORS.contains(&color.text) {
+            return Err(color.error(ErrorKind::UnknownColor(color.text.to_string())));
+        }
+        result.add(color.text.to_string(), color_count);
     }
-    return hand;
+    return Ok(result);
 }
 
-fn parse_games(games_str: &str) -> Vec<Game> {
-    let game_re = Regex::new(r"Game (?<id>\d+):(?<hands>.*)").unwrap();
-
-    return games_str
-        .split('\n')
-        .map(|line| {
-            let line_cap = game_re.captures(line).unwrap();
-            let hands_cap = line_cap.name("hands").expect("no hands").as_str();
-
-            let game_hands: Vec<Dict<i32>> = hands_cap.split(';').map(parse_hand).collect();
-
-            let game_id: i32 = line_cap
-                .name("id")
-                .expect("invalid game id")
-                .as_str()
-                .parse()
-                .unwrap();
-
-            return Game {
-                id: game_id,
-                hands: game_hands,
-            };
-        })
-        .collect();
+fn parse_games(games_str: &str) -> Result<Vec<Game>, ParseError<ErrorKind>> {
+    let mut games = vec![];
+    for line in parse::lines(games_str) {
+        let (id, hands) = line
+            .strip_prefix("Game ")
+            .and_then(|game| game.split_once(":"))
+            .ok_or(ParseError::new(line.line_no, 1, ErrorKind::MissingGameId))?;
+        let game_hands = hands
+            .split(";")
+            .map(parse_hand)
+            .collect::<Result<Vec<Dict<i32>>, _>>()?;
+        let game_id: i32 = id.parse(|x| ErrorKind::InvalidNumber(x.to_string()))?;
+
+        games.push(Game {
+            id: game_id,
+            hands: game_hands,
+        });
+    }
+    return Ok(games);
 }
 
-fn part1(games_input: &str, constraint_input: &str) -> i32 {
-    let constraint = parse_hand(constraint_input);
+fn possible_games(games: &[Game], constraint_input: &str) -> i32 {
+    let constraint = parse_hand(Span::new(1, constraint_input)).expect("invalid constraint");
 
-    return parse_games(games_input)
-        .into_iter()
+    return games
+        .iter()
         .filter_map(|game| {
-            if game.hands.into_iter().all(|hand| {
-                hand.into_iter()
+            if game.hands.iter().all(|hand| {
+                hand.iter()
                     .all(|kvp| constraint.get(&kvp.key).unwrap() >= &kvp.val)
             }) {
                 return Some(game.id);
@@ -71,14 +92,14 @@ fn part1(games_input: &str, constraint_input: &str) -> i32 {
         .sum();
 }
 
-fn part2(games_input: &str) -> i32 {
-    return parse_games(games_input)
-        .into_iter()
+fn power_sum(games: &[Game]) -> i32 {
+    return games
+        .iter()
         .map(|game| {
             let mut pow = Dict::<i32>::new();
-            for hand in game.hands {
+            for hand in &game.hands {
                 for set in hand {
-                    let color = set.key;
+                    let color = set.key.clone();
                     let value = set.val;
                     match pow.get(&color) {
                         Some(&existing) => {
@@ -98,12 +119,28 @@ fn part2(games_input: &str) -> i32 {
         .sum();
 }
 
+impl Solution for Day02 {
+    type Parsed = Vec<Game>;
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        parse_games(input)
+    }
+
+    fn part1(games: &Self::Parsed) -> Answer {
+        possible_games(games, PART1_CONSTRAINT).into()
+    }
+
+    fn part2(games: &Self::Parsed) -> Answer {
+        power_sum(games).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::utils;
 
-    const PART1_CONSTRAINT: &str = "12 red, 13 green, 14 blue";
     const EXAMPLE: &str = r#"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
 Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
 Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
@@ -111,7 +148,7 @@ Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
     #[test]
     fn part1_example() {
-        let result = part1(EXAMPLE, PART1_CONSTRAINT);
+        let result = Day02::part1(&Day02::parse(EXAMPLE).unwrap());
 
         assert_eq!(8, result);
     }
@@ -119,19 +156,32 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
     #[test]
     fn part1_result() {
         let input = utils::resource("src/day02.txt");
-        let result = part1(&input, PART1_CONSTRAINT);
+        let result = Day02::part1(&Day02::parse(&input).unwrap());
         assert_eq!(result, 2061);
     }
 
     #[test]
     fn part2_example() {
-        let result = part2(EXAMPLE);
+        let result = Day02::part2(&Day02::parse(EXAMPLE).unwrap());
         assert_eq!(result, 2286);
     }
     #[test]
     fn part2_result() {
         let input = utils::resource("src/day02.txt");
-        let result = part2(&input);
+        let result = Day02::part2(&Day02::parse(&input).unwrap());
         assert_eq!(result, 72596);
     }
+
+    #[test]
+    fn parse_error() {
+        let result = Day02::parse("Game 1: 3 blue, 4 red\nGame 2: 1 blue; 2 purple");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(
+                2,
+                19,
+                ErrorKind::UnknownColor("purple".to_string())
+            ))
+        );
+    }
 }
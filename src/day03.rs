@@ -1,38 +1,62 @@
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
+use crate::utils::parse::{self, Span};
 use crate::utils::Point2D;
-use regex::Regex;
 use std::collections::HashMap;
+use thiserror::Error;
 
-fn solve(input: &str) -> (i32, i32) {
-    let mut line_number = 0;
+pub struct Day03;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("unexpected character {0:?}, expected a digit, '.' or a symbol")]
+    UnexpectedCharacter(char),
+    #[error("part number '{0}' is too large")]
+    NumberTooLarge(String),
+}
+
+impl Hint for ErrorKind {
+    fn hint(&self) -> Option<String> {
+        Some("the schematic is made of part numbers, '.' and symbols like '*' or '#'".to_string())
+    }
+}
+
+/// A number in the engine schematic, together with all the symbols around it
+pub struct PartNumber {
+    number: i32,
+    symbols: Vec<(char, Point2D)>,
+}
+
+fn parse_schematic(input: &str) -> Result<Vec<PartNumber>, ParseError<ErrorKind>> {
+    let lines: Vec<Span> = parse::lines(input).collect();
     let mut symbols = HashMap::<Point2D, char>::new();
-    let number_re = Regex::new(r"[0-9]+").unwrap();
-    let symbol_re = Regex::new(r"[^.0-9]").unwrap(); // TODO are numbers symbols too?
-    let lines: Vec<&str> = input.split('\n').map(|ln| ln).collect();
-    for line in (&lines).into_iter() {
-        for symbol_match in symbol_re.find_iter(line) {
-            let point = Point2D {
-                x: symbol_match.start() as i32,
-                y: line_number,
-            };
-            symbols.insert(point, symbol_match.as_str().chars().nth(0).unwrap());
+    for line in lines.iter() {
+        line.check_chars(
+            |ch| ch.is_ascii_digit() || ch.is_ascii_punctuation(),
+            ErrorKind::UnexpectedCharacter,
+        )?;
+        let y = line.line_no as i32 - 1;
+        for (x, ch) in line.text.char_indices() {
+            if ch != '.' && !ch.is_ascii_digit() {
+                symbols.insert(Point2D::new(x as i32, y), ch);
+            }
         }
-        line_number += 1;
     }
 
-    let mut sum_all = 0;
-    line_number = 0;
-    let mut asterisks_adj = HashMap::<Point2D, Vec<i32>>::new();
-    for line in (&lines).into_iter() {
-        for n in number_re.find_iter(line) {
+    let mut numbers = Vec::<PartNumber>::new();
+    for line in lines.iter() {
+        let line_number = line.line_no as i32 - 1;
+        for n in line.runs(|ch| ch.is_ascii_digit()) {
             let mut border = Vec::<Point2D>::new();
-            let col_prev = n.start() as i32 - 1;
-            let col_next = n.end() as i32;
+            let col_prev = n.column() as i32 - 2;
+            let col_next = col_prev + n.text.len() as i32 + 1;
             let line_up = line_number - 1;
             let line_dn = line_number + 1;
-            let number = n.as_str().parse::<i32>().expect("expected integer");
-            for x in n.range() {
-                border.push((x as i32, line_up).into());
-                border.push((x as i32, line_dn).into());
+            let number = n.parse(|x| ErrorKind::NumberTooLarge(x.to_string()))?;
+            for x in col_prev + 1..col_next {
+                border.push((x, line_up).into());
+                border.push((x, line_dn).into());
             }
             for y in line_up..line_dn + 1 {
                 border.push((col_prev, y).into());
@@ -42,29 +66,49 @@ fn solve(input: &str) -> (i32, i32) {
                 .into_iter()
                 .filter_map(|b| symbols.get(&b).map(|c| (*c, b)))
                 .collect();
-            if symbols.len() > 0 {
-                sum_all += number;
-            }
-            for asterisk_pos in symbols.into_iter().filter_map(|x| match x.0 {
+            numbers.push(PartNumber { number, symbols });
+        }
+    }
+    return Ok(numbers);
+}
+
+impl Solution for Day03 {
+    type Parsed = Vec<PartNumber>;
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        parse_schematic(input)
+    }
+
+    fn part1(numbers: &Self::Parsed) -> Answer {
+        numbers
+            .iter()
+            .filter(|n| !n.symbols.is_empty())
+            .map(|n| n.number)
+            .sum::<i32>()
+            .into()
+    }
+
+    fn part2(numbers: &Self::Parsed) -> Answer {
+        let mut asterisks_adj = HashMap::<Point2D, Vec<i32>>::new();
+        for n in numbers {
+            for asterisk_pos in n.symbols.iter().filter_map(|x| match x.0 {
                 '*' => Some(x.1),
                 _ => None,
             }) {
-                let existing = asterisks_adj.get_mut(&asterisk_pos);
-                if existing.is_some() {
-                    existing.unwrap().push(number);
-                } else {
-                    asterisks_adj.insert(asterisk_pos, vec![number]);
-                }
+                asterisks_adj
+                    .entry(asterisk_pos)
+                    .or_default()
+                    .push(n.number);
             }
         }
-        line_number += 1;
-    }
 
-    let gear_ratios = asterisks_adj
-        .into_iter()
-        .filter(|(_, numbers)| numbers.len() > 1)
-        .map(|(_, numbers)| numbers.into_iter().fold(1, |agg, next| agg * next));
-    return (sum_all, gear_ratios.sum());
+        let gear_ratios = asterisks_adj
+            .into_iter()
+            .filter(|(_, numbers)| numbers.len() > 1)
+            .map(|(_, numbers)| numbers.into_iter().product::<i32>());
+        return gear_ratios.sum::<i32>().into();
+    }
 }
 
 #[cfg(test)]
@@ -86,7 +130,7 @@ mod tests {
         "#;
     #[test]
     fn part1_example() {
-        let result = solve(EXAMPLE.trim()).0;
+        let result = Day03::part1(&Day03::parse(EXAMPLE.trim()).unwrap());
 
         assert_eq!(result, 4361);
     }
@@ -94,14 +138,14 @@ mod tests {
     #[test]
     fn part1_result() {
         let input = utils::resource("src/day03.txt");
-        let result = solve(&input).0;
+        let result = Day03::part1(&Day03::parse(&input).unwrap());
 
         assert_eq!(result, 540212);
     }
 
     #[test]
     fn part2_example() {
-        let result = solve(EXAMPLE.trim()).1;
+        let result = Day03::part2(&Day03::parse(EXAMPLE.trim()).unwrap());
 
         assert_eq!(result, 467835);
     }
@@ -109,8 +153,28 @@ mod tests {
     #[test]
     fn part2_result() {
         let input = utils::resource("src/day03.txt");
-        let result = solve(&input).1;
+        let result = Day03::part2(&Day03::parse(&input).unwrap());
 
         assert_eq!(result, 87605697);
     }
+
+    #[test]
+    fn parse_error() {
+        let result = Day03::parse("467..114..\n...*..a...");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(2, 7, ErrorKind::UnexpectedCharacter('a')))
+        );
+        // line endings are not part of the lines
+        assert!(Day03::parse("467..114..\r\n...*......").is_ok());
+        let result = Day03::parse("467..11400000000..\n...*......");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(
+                1,
+                6,
+                ErrorKind::NumberTooLarge("11400000000".to_string())
+            ))
+        );
+    }
 }
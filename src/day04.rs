@@ -1,31 +1,43 @@
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
+use crate::utils::parse;
 use std::collections::HashSet;
+use thiserror::Error;
 
-fn parse_numbers<'a>(numbers_str: &'a str) -> HashSet<i32> {
-    return numbers_str
-        .split(' ')
-        .filter_map(|s| match s.parse::<i32>() {
-            Ok(x) => Some(x),
-            _ => None,
-        })
-        .collect();
+pub struct Day04;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("expected 'Card <id>: <winning numbers> | <numbers you have>'")]
+    InvalidCard,
+    #[error("invalid number '{0}'")]
+    InvalidNumber(String),
+}
+
+impl Hint for ErrorKind {
+    fn hint(&self) -> Option<String> {
+        Some("cards look like 'Card 1: 41 48 83 | 83 86  6 31'".to_string())
+    }
 }
 
-fn winners(input: &str) -> Vec<usize> {
-    input
-        .split('\n')
-        .map(|ln| {
-            let id_and_sets: Vec<&str> = ln.split(':').collect();
-            let number_sets: Vec<&str> = id_and_sets[1].split('|').collect();
+fn winners(input: &str) -> Result<Vec<usize>, ParseError<ErrorKind>> {
+    parse::lines(input)
+        .map(|line| {
+            let (win_str, my_str) = line
+                .split_once(":")
+                .and_then(|(_, sets)| sets.split_once("|"))
+                .ok_or(ParseError::new(line.line_no, 1, ErrorKind::InvalidCard))?;
 
-            let win_set = parse_numbers(number_sets[0]);
-            let my_set = parse_numbers(number_sets[1]);
-            return my_set.into_iter().filter(|my| win_set.contains(my)).count();
+            let invalid = |x: &str| ErrorKind::InvalidNumber(x.to_string());
+            let win_set: HashSet<i32> = win_str.numbers(invalid)?.into_iter().collect();
+            let my_set: HashSet<i32> = my_str.numbers(invalid)?.into_iter().collect();
+            return Ok(my_set.into_iter().filter(|my| win_set.contains(my)).count());
         })
         .collect()
 }
 
-fn part1(input: &str) -> i32 {
-    let winners = winners(input);
+fn points(winners: &[usize]) -> i32 {
     let base_two: i32 = 2;
     return winners
         .iter()
@@ -39,13 +51,11 @@ fn part1(input: &str) -> i32 {
         .sum();
 }
 
-fn part2(input: &str) -> u32 {
-    let winners = winners(input);
-
+fn total_cards(winners: &[usize]) -> u32 {
     let mut counts: Vec<u32> = winners.iter().map(|_| 1).collect();
     let card_counts_len = counts.len();
 
-    let mut card_id = 0 as usize;
+    let mut card_id = 0_usize;
     loop {
         if card_id >= card_counts_len {
             break;
@@ -66,6 +76,23 @@ fn part2(input: &str) -> u32 {
     counts.iter().sum()
 }
 
+impl Solution for Day04 {
+    type Parsed = Vec<usize>;
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        winners(input)
+    }
+
+    fn part1(winners: &Self::Parsed) -> Answer {
+        points(winners).into()
+    }
+
+    fn part2(winners: &Self::Parsed) -> Answer {
+        total_cards(winners).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -82,26 +109,44 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
 
     #[test]
     fn part1_example() {
-        let result = part1(EXAMPLE.trim());
+        let result = Day04::part1(&Day04::parse(EXAMPLE.trim()).unwrap());
         assert_eq!(13, result);
     }
     #[test]
     fn part1_result() {
         let input = utils::resource("src/day04.txt");
-        let result = part1(&input);
+        let result = Day04::part1(&Day04::parse(&input).unwrap());
 
         assert_eq!(25183, result);
     }
     #[test]
     fn part2_example() {
-        let result = part2(EXAMPLE.trim());
+        let result = Day04::part2(&Day04::parse(EXAMPLE.trim()).unwrap());
         assert_eq!(30, result);
     }
     #[test]
     fn part2_result() {
         let input = utils::resource("src/day04.txt");
-        let result = part2(&input);
+        let result = Day04::part2(&Day04::parse(&input).unwrap());
 
         assert_eq!(5667240, result);
     }
+
+    #[test]
+    fn parse_error() {
+        let result = Day04::parse("Card 1: 41 48 | 83 86\nCard 2: 13 32 | 6l 30");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(
+                2,
+                17,
+                ErrorKind::InvalidNumber("6l".to_string())
+            ))
+        );
+        let result = Day04::parse("Card 1: 41 48 83 86");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(1, 1, ErrorKind::InvalidCard))
+        );
+    }
 }
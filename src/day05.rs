@@ -1,138 +1,159 @@
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
+use crate::utils::parse::{self, Span};
+use crate::utils::RangeSet;
+use thiserror::Error;
+
 type Int = i64;
 type Range = std::ops::Range<Int>;
 
+pub struct Day05;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("expected 'seeds: <numbers>' on the first line")]
+    MissingSeeds,
+    #[error("expected '<destination> <source> <length>'")]
+    ExpectedThreeNumbers,
+    #[error("invalid number '{0}'")]
+    InvalidNumber(String),
+    #[error("expected a length after the last seed")]
+    UnpairedSeed,
+    #[error("expected a positive length")]
+    EmptySeedRange,
+}
+
+impl Hint for ErrorKind {
+    fn hint(&self) -> Option<String> {
+        match self {
+            ErrorKind::MissingSeeds => {
+                Some("the almanac starts with 'seeds: 79 14 55 13'".to_string())
+            }
+            ErrorKind::UnpairedSeed | ErrorKind::EmptySeedRange => Some(
+                "the seeds are pairs of a start and a length, like 'seeds: 79 14 55 13'"
+                    .to_string(),
+            ),
+            _ => Some(
+                "maps are a header like 'seed-to-soil map:' and ranges like '50 98 2'".to_string(),
+            ),
+        }
+    }
+}
+
+pub struct Almanac {
+    seeds: Vec<Int>,
+    layers: Vec<Vec<IntMap>>,
+}
+
 struct IntMap {
     source: Range,
     destination: Range,
 }
 
-impl IntMap {
-    fn translate(self: &IntMap, value: &Int) -> Int {
-        let offset = value.clone() - self.source.start;
-        let destination = self.destination.start + offset;
-        return destination;
+fn parse_mapping(line: &Span) -> Result<IntMap, ParseError<ErrorKind>> {
+    let split: Vec<Int> = line.numbers(|x| ErrorKind::InvalidNumber(x.to_string()))?;
+    if split.len() != 3 {
+        return Err(ParseError::new(
+            line.line_no,
+            1,
+            ErrorKind::ExpectedThreeNumbers,
+        ));
     }
-}
-
-fn parse_mapping(line: &str) -> IntMap {
-    let split: Vec<Int> = line.split(' ').map(|x| x.parse().unwrap()).collect();
-    IntMap {
+    Ok(IntMap {
         destination: split[0]..(split[0] + split[2]),
         source: split[1]..(split[1] + split[2]),
-    }
+    })
 }
 
-fn parse_layers(lines: &Vec<&str>) -> Vec<Vec<IntMap>> {
-    let mut layers = Vec::<Vec<IntMap>>::new();
-    let mut builder = Vec::<IntMap>::new();
-    for line in lines.iter() {
-        if line.is_empty() {
-        } else if line.contains("-to-") {
-            if builder.len() > 0 {
-                let mut layer = Vec::<IntMap>::new();
-                layer.append(&mut builder); // this also clears the builder
-                layers.push(layer);
-            }
-        } else {
-            builder.push(parse_mapping(line));
+/// part 2 reads the seeds as pairs of a start and a length, there has to be at least one
+fn parse_seeds(numbers: &Span) -> Result<Vec<Int>, ParseError<ErrorKind>> {
+    let words: Vec<Span> = numbers.words().collect();
+    let seeds = words
+        .iter()
+        .map(|word| word.parse(|x| ErrorKind::InvalidNumber(x.to_string())))
+        .collect::<Result<Vec<Int>, _>>()?;
+    match words.last() {
+        None => return Err(numbers.error_after(ErrorKind::MissingSeeds)),
+        Some(last) if words.len() % 2 == 1 => {
+            return Err(last.error_after(ErrorKind::UnpairedSeed));
         }
+        _ => {}
     }
-    layers.push(builder);
-    return layers;
-}
-
-fn intersect(a: &Range, b: &Range) -> Option<Range> {
-    if a.contains(&b.start) {
-        return Some(b.start..std::cmp::min(a.end, b.end));
-    }
-
-    if b.contains(&a.start) {
-        return Some(a.start..std::cmp::min(a.end, b.end));
+    if let Some(i) = (1..seeds.len()).step_by(2).find(|&i| seeds[i] <= 0) {
+        return Err(words[i].error(ErrorKind::EmptySeedRange));
     }
-
-    return None;
+    return Ok(seeds);
 }
 
-fn solve(seed: &Vec<Range>, layers: &Vec<Vec<IntMap>>) -> Int {
-    let results = layers.iter().fold(seed.clone(), |agg, layer| {
-        let mut next = Vec::<Range>::new();
-        let mut todo = agg.into_iter().collect::<Vec<Range>>();
-        while todo.len() > 0 {
-            let cur = todo.pop().unwrap();
-
-            let mut was_mapped = false;
-            for map in layer {
-                let int_opt = intersect(&cur, &map.source);
-                if int_opt.is_none() {
-                    continue;
-                }
-
-                let int = int_opt.unwrap();
-                let dest = map.translate(&int.start)..map.translate(&int.end);
-                next.push(dest);
-
-                // the range might split in 3 by the intersection, we might still need to process excess at the beginnig and end
-                if cur.start < int.start {
-                    todo.push(cur.start..int.start);
-                }
-                if int.end < cur.end {
-                    todo.push(int.end..cur.end);
-                }
-                was_mapped = true;
-            }
+/// every map is a section with a header like 'seed-to-soil map:'
+fn parse_layer(section: &[Span]) -> Result<Vec<IntMap>, ParseError<ErrorKind>> {
+    let lines = parse::labelled(section).map_or(section, |(_, lines)| lines);
+    return lines.iter().map(parse_mapping).collect();
+}
 
-            if !was_mapped {
-                next.push(cur);
-            }
+fn solve(seeds: RangeSet<Int>, layers: &[Vec<IntMap>]) -> Int {
+    let locations = layers.iter().fold(seeds, |current, layer| {
+        // values in a source range are moved to the destination, the rest stays where it is
+        let mut unmapped = current.clone();
+        let mut next = RangeSet::new();
+        for map in layer {
+            let source = RangeSet::from(map.source.clone());
+            let offset = map.destination.start - map.source.start;
+            next = next.union(&current.intersection(&source).shift(offset));
+            unmapped = unmapped.difference(&source);
         }
-        return next;
+        return next.union(&unmapped);
     });
-    return results.into_iter().map(|s| s.start).min().unwrap();
+    return locations.min().expect("parse_seeds checks there are seeds");
 }
 
-fn part1(input: &str) -> Int {
-    let lines: Vec<&str> = input.trim().split('\n').collect();
-    let seed_ranges: Vec<Range> = lines[0]
-        .split(' ')
-        .skip(1)
-        .map(|x| x.parse().unwrap())
-        .map(|x| x..(x + 1))
-        .collect();
-
-    let layers = parse_layers(&lines.into_iter().skip(2).collect());
-    return solve(&seed_ranges, &layers);
-}
+impl Solution for Day05 {
+    type Parsed = Almanac;
+    type ErrorKind = ErrorKind;
 
-fn part2(input: &str) -> Int {
-    let lines: Vec<&str> = input.trim().split('\n').collect();
-    let seed_numbers: Vec<Int> = lines[0]
-        .split(' ')
-        .skip(1)
-        .map(|x| x.parse().unwrap())
-        .collect();
-
-    let mut seed_ranges = Vec::<Range>::new();
-    for i in 0..seed_numbers.len() {
-        if i % 2 == 1 {
-            continue;
-        }
-        let start = seed_numbers[i];
-        let end = seed_numbers[i] + seed_numbers[i + 1];
-        seed_ranges.push(start..end);
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        let sections = parse::sections(input);
+        let first = sections.first().map(|section| section[0]);
+        let seeds = match first.and_then(|line| line.header("seeds")) {
+            Some(numbers) => parse_seeds(&numbers)?,
+            None => {
+                let line_no = first.map_or(1, |line| line.line_no);
+                return Err(ParseError::new(line_no, 1, ErrorKind::MissingSeeds));
+            }
+        };
+        let layers = sections
+            .iter()
+            .skip(1)
+            .map(|section| parse_layer(section))
+            .collect::<Result<_, _>>()?;
+        return Ok(Almanac { seeds, layers });
     }
 
-    let layers = parse_layers(&lines.into_iter().skip(2).collect());
-    return solve(&seed_ranges, &layers);
+    fn part1(almanac: &Self::Parsed) -> Answer {
+        let seeds = almanac.seeds.iter().map(|&x| x..(x + 1)).collect();
+        return solve(seeds, &almanac.layers).into();
+    }
+
+    fn part2(almanac: &Self::Parsed) -> Answer {
+        let seeds = almanac
+            .seeds
+            .chunks_exact(2)
+            .map(|pair| pair[0]..(pair[0] + pair[1]))
+            .collect();
+        return solve(seeds, &almanac.layers).into();
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::differential::{self, Rng};
     use crate::utils::resource;
     #[test]
     fn part1_example() {
         let input = resource("src/day05.example.txt");
-        let result = part1(&input);
+        let result = Day05::part1(&Day05::parse(&input).unwrap());
 
         assert_eq!(35, result);
     }
@@ -140,7 +161,7 @@ mod tests {
     #[test]
     fn part1_result() {
         let input = resource("src/day05.txt");
-        let result = part1(&input);
+        let result = Day05::part1(&Day05::parse(&input).unwrap());
 
         assert_eq!(1181555926, result);
     }
@@ -148,15 +169,95 @@ mod tests {
     #[test]
     fn part2_example() {
         let input = resource("src/day05.example.txt");
-        let result = part2(&input);
+        let result = Day05::part2(&Day05::parse(&input).unwrap());
 
         assert_eq!(46, result);
     }
     #[test]
     fn part2_result() {
         let input = resource("src/day05.txt");
-        let result = part2(&input);
+        let result = Day05::part2(&Day05::parse(&input).unwrap());
 
         assert_eq!(37806486, result);
     }
+
+    #[test]
+    fn parse_error() {
+        let result = Day05::parse("seeds: 79 14\n\nseed-to-soil map:\n50 98\n");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(4, 1, ErrorKind::ExpectedThreeNumbers))
+        );
+        let result = Day05::parse("seeds: 79 x4\n");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(
+                1,
+                11,
+                ErrorKind::InvalidNumber("x4".to_string())
+            ))
+        );
+        let result = Day05::parse("seeds:\n\na map:\n1 2 3\n");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(1, 7, ErrorKind::MissingSeeds))
+        );
+        let result = Day05::parse("seeds: 5\n\na map:\n1 2 3\n");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(1, 9, ErrorKind::UnpairedSeed))
+        );
+        let result = Day05::parse("seeds: 5 0\n\na map:\n1 2 3\n");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(1, 10, ErrorKind::EmptySeedRange))
+        );
+    }
+
+    /// a small almanac, the source ranges of a map don't overlap
+    fn generate(rng: &mut Rng) -> String {
+        let mut text = "seeds:".to_string();
+        for _ in 0..rng.range(1..4) {
+            text += &format!(" {} {}", rng.range(0..60), rng.range(1..20));
+        }
+        for layer in 0..rng.range(1..5) {
+            text += &format!("\n\nlayer-{} map:", layer);
+            let mut start = rng.range(0..20);
+            for _ in 0..rng.range(0..5) {
+                let length = rng.range(1..20);
+                text += &format!("\n{} {} {}", rng.range(0..80), start, length);
+                start += length + rng.range(0..10);
+            }
+        }
+        return text;
+    }
+
+    /// every seed on its own, through the first map containing it
+    fn brute_force(almanac: &Almanac) -> Int {
+        let seeds = almanac
+            .seeds
+            .chunks_exact(2)
+            .flat_map(|pair| pair[0]..(pair[0] + pair[1]));
+        return seeds
+            .map(|seed| {
+                almanac.layers.iter().fold(seed, |value, layer| {
+                    match layer.iter().find(|map| map.source.contains(&value)) {
+                        Some(map) => value - map.source.start + map.destination.start,
+                        None => value,
+                    }
+                })
+            })
+            .min()
+            .unwrap();
+    }
+
+    #[test]
+    fn part2_differential() {
+        differential::cross_check(
+            200,
+            generate,
+            |input| Day05::part2(&Day05::parse(input).unwrap()),
+            |input| brute_force(&Day05::parse(input).unwrap()).into(),
+        );
+    }
 }
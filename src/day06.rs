@@ -1,12 +1,39 @@
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
+use crate::utils::parse::{self, Span};
+use thiserror::Error;
+
 type Int = i128;
 
-fn solve(times: &Vec<Int>, records: &Vec<Int>) -> Int {
+pub struct Day06;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("expected a 'Time:' and a 'Distance:' line")]
+    ExpectedTwoLines,
+    #[error("expected '{0}:'")]
+    MissingLabel(&'static str),
+    #[error("invalid number '{0}'")]
+    InvalidNumber(String),
+    #[error("expected as many distances as there are times")]
+    MismatchedCounts,
+}
+
+impl Hint for ErrorKind {
+    fn hint(&self) -> Option<String> {
+        Some("the sheet looks like 'Time:      7  15   30' and 'Distance:  9  40  200'".to_string())
+    }
+}
+
+const LABELS: [&str; 2] = ["Time", "Distance"];
+
+fn solve(times: &[Int], records: &[Int]) -> Int {
     return times
         .iter()
         .zip(records.iter())
         .map(|(&time, &record)| {
             let options: Vec<Int> = (1..time)
-                .into_iter()
                 .filter_map(|hodl| {
                     let speed = hodl;
                     let remaining = time - speed;
@@ -22,39 +49,47 @@ fn solve(times: &Vec<Int>, records: &Vec<Int>) -> Int {
         .fold(1 as Int, |agg, next| agg * next);
 }
 
-fn part1(input: &str) -> Int {
-    let lines: Vec<Vec<Int>> = input
-        .trim()
-        .split('\n')
-        .map(|line| {
-            return line
-                .split(' ')
-                .filter_map(|s| s.parse::<Int>().ok())
-                .collect();
-        })
-        .collect();
-    let lines_ref = &lines;
-    return solve(&lines_ref[0], &lines_ref[1]);
+/// joins the numbers of the race sheet, since they were separated by bad kerning
+fn unkern(numbers: &[Int]) -> Vec<Int> {
+    let joined = numbers.iter().map(|x| x.to_string()).collect::<String>();
+    return vec![joined.parse::<Int>().unwrap()];
 }
 
-fn part2(input: &str) -> Int {
-    let lines: Vec<Vec<Int>> = input
-        .trim()
-        .split('\n')
-        .map(|line| {
-            line.split(':')
-                .nth(1)
-                .unwrap()
-                .split(' ')
-                .collect::<Vec<&str>>()
-                .join("")
-                .parse::<Int>()
-                .unwrap()
-        })
-        .map(|single| vec![single])
-        .collect();
-    let lines_ref = &lines;
-    return solve(&lines_ref[0], &lines_ref[1]);
+impl Solution for Day06 {
+    type Parsed = Vec<Vec<Int>>;
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        let lines: Vec<Span> = parse::lines(input).collect();
+        if lines.len() != LABELS.len() {
+            let line_no = lines.get(LABELS.len()).map_or(1, |line| line.line_no);
+            return Err(ParseError::new(line_no, 1, ErrorKind::ExpectedTwoLines));
+        }
+        let parsed = lines
+            .iter()
+            .zip(LABELS)
+            .map(|(line, label)| {
+                let numbers = line.header(label).ok_or(ParseError::new(
+                    line.line_no,
+                    1,
+                    ErrorKind::MissingLabel(label),
+                ))?;
+                return numbers.numbers(|x| ErrorKind::InvalidNumber(x.to_string()));
+            })
+            .collect::<Result<Vec<Vec<Int>>, _>>()?;
+        if parsed[0].len() != parsed[1].len() {
+            return Err(lines[1].error_after(ErrorKind::MismatchedCounts));
+        }
+        return Ok(parsed);
+    }
+
+    fn part1(lines: &Self::Parsed) -> Answer {
+        return solve(&lines[0], &lines[1]).into();
+    }
+
+    fn part2(lines: &Self::Parsed) -> Answer {
+        return solve(&unkern(&lines[0]), &unkern(&lines[1])).into();
+    }
 }
 
 #[cfg(test)]
@@ -68,27 +103,50 @@ Distance:  9  40  200
 
     #[test]
     fn part1_example() {
-        let result = part1(&EXAMPLE);
+        let result = Day06::part1(&Day06::parse(EXAMPLE).unwrap());
         assert_eq!(288, result);
     }
 
     #[test]
     fn part1_result() {
         let input = resource("src/day06.txt");
-        let result = part1(&input);
+        let result = Day06::part1(&Day06::parse(&input).unwrap());
         assert_eq!(1660968, result);
     }
 
     #[test]
     fn part2_example() {
-        let result = part2(&EXAMPLE);
+        let result = Day06::part2(&Day06::parse(EXAMPLE).unwrap());
         assert_eq!(71503, result);
     }
 
     #[test]
     fn part2_result() {
         let input = resource("src/day06.txt");
-        let result = part2(&input);
+        let result = Day06::part2(&Day06::parse(&input).unwrap());
         assert_eq!(26499773, result);
     }
+
+    #[test]
+    fn parse_error() {
+        let result = Day06::parse("Time: 7 15\nDistance: 9 4O");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(
+                2,
+                13,
+                ErrorKind::InvalidNumber("4O".to_string())
+            ))
+        );
+        let result = Day06::parse("Time: 7 15\nDistance: 9");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(2, 12, ErrorKind::MismatchedCounts))
+        );
+        let result = Day06::parse("Time: 7 15");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(1, 1, ErrorKind::ExpectedTwoLines))
+        );
+    }
 }
@@ -1,9 +1,36 @@
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
+use crate::utils::parse;
 use std::collections::HashMap;
+use thiserror::Error;
 
 type Int = u128;
 type Card = u8;
 const SHIFT: Int = 8; // 8 from u8 in Card
-type Hand = Vec<char>;
+
+pub struct Day07;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("expected '<hand> <bet>'")]
+    MissingBet,
+    #[error("expected a hand of 5 cards")]
+    InvalidHand,
+    #[error("invalid card {0:?}")]
+    InvalidCard(char),
+    #[error("invalid bet '{0}'")]
+    InvalidBet(String),
+}
+
+impl Hint for ErrorKind {
+    fn hint(&self) -> Option<String> {
+        Some(format!(
+            "hands look like '32T3K 765', five of '{}' and the bet",
+            ORDER_1
+        ))
+    }
+}
 
 const ORDER_1: &str = "23456789TJQKA";
 const ORDER_2: &str = "J23456789TQKA";
@@ -57,19 +84,30 @@ fn hand_power(hand: &str, order: &'static str, joker: &'static char) -> Int {
 
     // now add the remainder of cards in order, so the last card is the lowest byte
     hand.chars()
-        .map(|ch| order.find(ch).expect("unexpected character") as u8)
+        .map(|ch| order.find(ch).expect("unexpected character") as Card)
         .fold(upper_type_bits, |agg, card| (agg << SHIFT) + (card as Int))
 }
 
-fn solve(input: &str, order: &'static str, joker: &'static char) -> Int {
-    let mut hands: Vec<(Int, Int, &str)> = input
-        .trim()
-        .split('\n')
+fn parse_hands(input: &str) -> Result<Vec<(String, Int)>, ParseError<ErrorKind>> {
+    parse::lines(input)
         .map(|line| {
-            let (hand_str, bet_str) = line.split_at(5);
-            let bet = bet_str.trim().parse::<Int>().expect("all bets are off");
-            return (hand_power(hand_str.trim(), order, joker), bet, hand_str);
+            let (hand, bet) = line
+                .split_once(" ")
+                .ok_or(line.error_after(ErrorKind::MissingBet))?;
+            hand.check_chars(|ch| ORDER_1.contains(ch), ErrorKind::InvalidCard)?;
+            if hand.text.len() != 5 {
+                return Err(ParseError::new(line.line_no, 1, ErrorKind::InvalidHand));
+            }
+            let bet = bet.trim().parse(|x| ErrorKind::InvalidBet(x.to_string()))?;
+            return Ok((hand.text.to_string(), bet));
         })
+        .collect()
+}
+
+fn solve(bets: &[(String, Int)], order: &'static str, joker: &'static char) -> Int {
+    let mut hands: Vec<(Int, Int, &str)> = bets
+        .iter()
+        .map(|(hand_str, bet)| (hand_power(hand_str, order, joker), *bet, hand_str.as_str()))
         .collect();
     hands.sort_by(|(a, _, _), (b, _, _)| PartialOrd::partial_cmp(a, b).unwrap());
 
@@ -80,6 +118,23 @@ fn solve(input: &str, order: &'static str, joker: &'static char) -> Int {
         .sum()
 }
 
+impl Solution for Day07 {
+    type Parsed = Vec<(String, Int)>;
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        parse_hands(input)
+    }
+
+    fn part1(hands: &Self::Parsed) -> Answer {
+        solve(hands, ORDER_1, NO_JOKER).into()
+    }
+
+    fn part2(hands: &Self::Parsed) -> Answer {
+        solve(hands, ORDER_2, JOKER).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -94,7 +149,7 @@ QQQJA 483"#;
 
     #[test]
     fn part1_example() {
-        let result = solve(EXAMPLE.trim(), ORDER_1, NO_JOKER);
+        let result = Day07::part1(&Day07::parse(EXAMPLE.trim()).unwrap());
 
         assert_eq!(6440, result);
     }
@@ -102,14 +157,14 @@ QQQJA 483"#;
     #[test]
     fn part1_result() {
         let input = resource("src/day07.txt");
-        let result = solve(input.trim(), ORDER_1, NO_JOKER);
+        let result = Day07::part1(&Day07::parse(input.trim()).unwrap());
 
         assert_eq!(250120186, result);
     }
 
     #[test]
     fn part2_example() {
-        let result = solve(EXAMPLE.trim(), ORDER_2, JOKER);
+        let result = Day07::part2(&Day07::parse(EXAMPLE.trim()).unwrap());
 
         assert_eq!(5905, result);
     }
@@ -117,8 +172,31 @@ QQQJA 483"#;
     #[test]
     fn part2_result() {
         let input = resource("src/day07.txt");
-        let result = solve(input.trim(), ORDER_2, JOKER);
+        let result = Day07::part2(&Day07::parse(input.trim()).unwrap());
 
         assert_eq!(250665248, result);
     }
+
+    #[test]
+    fn parse_error() {
+        let result = Day07::parse("32T3K 765\nT55X5 684");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(2, 4, ErrorKind::InvalidCard('X')))
+        );
+        let result = Day07::parse("32T3K 765\nT55J5 -684");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(
+                2,
+                7,
+                ErrorKind::InvalidBet("-684".to_string())
+            ))
+        );
+        let result = Day07::parse("32T3 765");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(1, 1, ErrorKind::InvalidHand))
+        );
+    }
 }
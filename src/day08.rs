@@ -1,41 +1,92 @@
-use regex::Regex;
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
+use crate::utils::parse::{self, Span};
 use std::collections::{HashMap, HashSet};
+use thiserror::Error;
 
-type Graph<'a> = HashMap<&'a str, (&'a str, &'a str)>;
+pub struct Day08;
 
-fn parse_input(input: &str) -> (Vec<char>, Graph) {
-    let lines: Vec<&str> = input.trim().split('\n').collect();
-    let parser = Regex::new(r"(?<node>.+) = \((?<left>[^,]+), (?<right>[^\)]+)\)").unwrap();
-    let instructions: Vec<char> = lines[0].chars().collect();
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("invalid instruction {0:?}, expected 'L' or 'R'")]
+    InvalidInstruction(char),
+    #[error("expected '<node> = (<left>, <right>)'")]
+    InvalidNode,
+    #[error("unknown node '{0}'")]
+    UnknownNode(String),
+    #[error("expected the 'L'/'R' instructions on the first line")]
+    MissingInstructions,
+}
+
+impl Hint for ErrorKind {
+    fn hint(&self) -> Option<String> {
+        match self {
+            ErrorKind::InvalidInstruction(_) | ErrorKind::MissingInstructions => None,
+            ErrorKind::InvalidNode => Some("nodes look like 'AAA = (BBB, CCC)'".to_string()),
+            ErrorKind::UnknownNode(name) => {
+                Some(format!("add a line for it, like '{0} = ({0}, {0})'", name))
+            }
+        }
+    }
+}
+
+type Graph = HashMap<String, (String, String)>;
+
+fn parse_input(input: &str) -> Result<(Vec<char>, Graph), ParseError<ErrorKind>> {
+    let mut lines = parse::lines(input);
+    let first = lines.next().unwrap_or(Span::new(1, ""));
+    first.check_chars(|ch| "LR".contains(ch), ErrorKind::InvalidInstruction)?;
+    if first.is_empty() {
+        return Err(first.error(ErrorKind::MissingInstructions));
+    }
+    let instructions: Vec<char> = first.text.chars().collect();
 
     let mut graph = Graph::new();
-    for ln in lines.into_iter().skip(2) {
-        let cap = parser.captures(ln).unwrap();
-        let name = cap.name("node").expect("node not parsed").as_str();
-        let left = cap.name("left").expect("left not parsed").as_str();
-        let right = cap.name("right").expect("right not parsed").as_str();
-        graph.insert(name, (left, right));
+    let mut references = Vec::<Span>::new();
+    for line in lines.filter(|line| !line.is_empty()) {
+        let (name, left, right) = match line.tuple() {
+            Some((name, targets)) if targets.len() == 2 && !name.is_empty() => {
+                (name, targets[0], targets[1])
+            }
+            _ => return Err(ParseError::new(line.line_no, 1, ErrorKind::InvalidNode)),
+        };
+        references.push(left);
+        references.push(right);
+        graph.insert(
+            name.text.to_string(),
+            (left.text.to_string(), right.text.to_string()),
+        );
     }
     let graph = graph;
 
-    return (instructions, graph);
+    // every node we can walk to has to be defined somewhere
+    if let Some(node) = references.iter().find(|n| !graph.contains_key(n.text)) {
+        return Err(node.error(ErrorKind::UnknownNode(node.text.to_string())));
+    }
+
+    return Ok((instructions, graph));
 }
 
 fn exit_path_length(
     start_node: &str,
     end_nodes: &HashSet<&str>,
-    instructions: &Vec<char>,
+    instructions: &[char],
     graph: &Graph,
-) -> usize {
+) -> Option<usize> {
     let mut node = start_node;
     let mut step = 0;
     let instructions_len = instructions.len();
     while !end_nodes.contains(node) {
-        let lr = *graph.get(node).expect(node);
+        // past every pair of a node and an instruction the walk only goes around in circles
+        if step >= instructions_len * graph.len() {
+            return None;
+        }
+        let (left, right) = graph.get(node)?;
         let step_idx = step % instructions_len;
         match instructions[step_idx] {
-            'L' => (node, _) = lr,
-            'R' => (_, node) = lr,
+            'L' => node = left,
+            'R' => node = right,
             x => panic!(
                 "unknown instruction {} at step {} in node {}",
                 x, step_idx, node
@@ -43,43 +94,68 @@ fn exit_path_length(
         }
         step += 1;
     }
-    return step;
+    return Some(step);
 }
 
-fn part1(input: &str) -> usize {
-    let (instructions, graph) = parse_input(input);
-    let exzzzit = &["ZZZ"].into_iter().collect::<HashSet<&str>>();
-    return exit_path_length("AAA", exzzzit, &instructions, &graph);
-}
+impl Solution for Day08 {
+    type Parsed = (Vec<char>, Graph);
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        parse_input(input)
+    }
+
+    fn part1((instructions, graph): &Self::Parsed) -> Answer {
+        // the example of part 2 doesn't have these
+        if let Some(missing) = ["AAA", "ZZZ"].iter().find(|n| !graph.contains_key(**n)) {
+            return Answer::Unsolvable(format!("there is no node '{}'", missing));
+        }
+        let exzzzit = &["ZZZ"].into_iter().collect::<HashSet<&str>>();
+        return match exit_path_length("AAA", exzzzit, instructions, graph) {
+            Some(steps) => steps.into(),
+            None => Answer::Unsolvable("'ZZZ' can't be reached from 'AAA'".to_string()),
+        };
+    }
 
-fn part2(input: &str) -> u128 {
-    let (instructions, graph) = parse_input(input);
-    let end_nodes: HashSet<&str> = graph
-        .keys()
-        .filter(|k| k.ends_with("Z"))
-        .map(|k| *k)
-        .collect();
-    return graph
-        .keys()
-        .filter(|k| k.ends_with("A"))
-        .map(|k| exit_path_length(*k, &end_nodes, &instructions, &graph))
-        .fold(1 as u128, |agg, next| num::integer::lcm(agg, next as u128));
+    fn part2((instructions, graph): &Self::Parsed) -> Answer {
+        let end_nodes: HashSet<&str> = graph
+            .keys()
+            .filter(|k| k.ends_with('Z'))
+            .map(|k| k.as_str())
+            .collect();
+        let lengths = graph
+            .keys()
+            .filter(|k| k.ends_with('A'))
+            .map(|k| exit_path_length(k, &end_nodes, instructions, graph))
+            .collect::<Option<Vec<usize>>>();
+        return match lengths {
+            Some(lengths) => lengths
+                .into_iter()
+                .fold(1_u128, |agg, next| num::integer::lcm(agg, next as u128))
+                .into(),
+            None => Answer::Unsolvable("a ghost never reaches a node ending in 'Z'".to_string()),
+        };
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::differential::{self, Rng};
     use crate::utils::*;
 
     #[test]
     fn part1_example() {
-        let result = part1(
-            r#"
+        let result = Day08::part1(
+            &Day08::parse(
+                r#"
 LLR
 
 AAA = (BBB, BBB)
 BBB = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)"#,
+            )
+            .unwrap(),
         );
 
         assert_eq!(6, result);
@@ -87,15 +163,16 @@ ZZZ = (ZZZ, ZZZ)"#,
 
     #[test]
     fn part1_result() {
-        let result = part1(&resource("src/day08.txt"));
+        let result = Day08::part1(&Day08::parse(&resource("src/day08.txt")).unwrap());
 
         assert_eq!(17873, result);
     }
 
     #[test]
     fn part2_example() {
-        let result = part2(
-            r#"
+        let result = Day08::part2(
+            &Day08::parse(
+                r#"
 LR
 
 11A = (11B, XXX)
@@ -107,6 +184,8 @@ LR
 22Z = (22B, 22B)
 XXX = (XXX, XXX)
 "#,
+            )
+            .unwrap(),
         );
 
         assert_eq!(6, result);
@@ -114,8 +193,107 @@ XXX = (XXX, XXX)
 
     #[test]
     fn part2_result() {
-        let result = part2(&resource("src/day08.txt"));
+        let result = Day08::part2(&Day08::parse(&resource("src/day08.txt")).unwrap());
+
+        assert_eq!(15746133679061_i64, result);
+    }
 
-        assert_eq!(15746133679061, result);
+    #[test]
+    fn parse_error() {
+        let result = Day08::parse("LLX\n\nAAA = (BBB, BBB)");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(1, 3, ErrorKind::InvalidInstruction('X')))
+        );
+        let result = Day08::parse("LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(
+                4,
+                13,
+                ErrorKind::UnknownNode("ZZZ".to_string())
+            ))
+        );
+        let result = Day08::parse("LLR\n\nAAA -> BBB");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(3, 1, ErrorKind::InvalidNode))
+        );
+        let result = Day08::parse("");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(1, 1, ErrorKind::MissingInstructions))
+        );
+    }
+
+    #[test]
+    fn part1_unsolvable() {
+        let result =
+            Day08::part1(&Day08::parse("LR\n\n11A = (11Z, 11Z)\n11Z = (11A, 11A)").unwrap());
+        assert_eq!(
+            result,
+            Answer::Unsolvable("there is no node 'AAA'".to_string())
+        );
+        let result =
+            Day08::part1(&Day08::parse("LR\n\nAAA = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)").unwrap());
+        assert_eq!(
+            result,
+            Answer::Unsolvable("'ZZZ' can't be reached from 'AAA'".to_string())
+        );
+    }
+
+    /// ghosts walking loops like the puzzle's: after reaching their Z node they go back to
+    /// the node after their A, and the left and right paths always have the same length
+    fn generate(rng: &mut Rng) -> String {
+        let instructions: String = (0..rng.range(1..6))
+            .map(|_| *rng.pick(&['L', 'R']))
+            .collect();
+        let mut nodes = vec![];
+        for ghost in 0..rng.range(1..5) {
+            let length = rng.range(1..13);
+            // the node `step` steps from the start on the left or right path
+            let node = |lane: char, step: i64| match step {
+                0 => format!("{}A", ghost),
+                x if x == length => format!("{}Z", ghost),
+                x => format!("{}{}{}", ghost, lane, x),
+            };
+            let after = |step: i64| (node('L', step + 1), node('R', step + 1));
+            nodes.push((node('L', 0), after(0)));
+            for step in 1..length {
+                nodes.push((node('L', step), after(step)));
+                nodes.push((node('R', step), after(step)));
+            }
+            nodes.push((node('L', length), after(0)));
+        }
+        let lines: Vec<String> = nodes
+            .iter()
+            .map(|(name, (left, right))| format!("{} = ({}, {})", name, left, right))
+            .collect();
+        return format!("{}\n\n{}", instructions, lines.join("\n"));
+    }
+
+    /// all the ghosts step together until they are all on a Z node
+    fn brute_force((instructions, graph): &(Vec<char>, Graph)) -> u128 {
+        let mut ghosts: Vec<&String> = graph.keys().filter(|k| k.ends_with('A')).collect();
+        let mut steps = 0;
+        while !ghosts.iter().all(|ghost| ghost.ends_with('Z')) {
+            let instruction = instructions[steps % instructions.len()];
+            for ghost in ghosts.iter_mut() {
+                let (left, right) = &graph[*ghost];
+                *ghost = if instruction == 'L' { left } else { right };
+            }
+            steps += 1;
+        }
+        return steps as u128;
+    }
+
+    #[test]
+    fn part2_differential() {
+        differential::cross_check(
+            100,
+            generate,
+            |input| Day08::part2(&Day08::parse(input).unwrap()),
+            |input| brute_force(&Day08::parse(input).unwrap()).into(),
+        );
     }
 }
@@ -1,18 +1,30 @@
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
+use crate::utils::parse;
+use thiserror::Error;
+
 type Int = isize;
 
-fn solve<T>(input: &str, func: T) -> Int
+pub struct Day09;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("invalid number '{0}'")]
+    InvalidNumber(String),
+}
+
+impl Hint for ErrorKind {
+    fn hint(&self) -> Option<String> {
+        Some("each line is a history of numbers, like '0 3 6 9 12 15'".to_string())
+    }
+}
+
+fn solve<T>(lines: &[Vec<Int>], func: T) -> Int
 where
     T: Fn(Vec<Int>) -> Int,
 {
-    let result = input
-        .split('\n')
-        .map(|line| {
-            line.split(' ')
-                .map(|x| x.parse::<Int>().unwrap())
-                .collect::<Vec<Int>>()
-        })
-        .map(|x| func(x))
-        .sum();
+    let result = lines.iter().cloned().map(func).sum();
     return result;
 }
 
@@ -30,26 +42,37 @@ fn solve_line_part1(line: Vec<Int>) -> Int {
     if line.iter().all(|&x| x == 0) {
         return 0;
     }
-    let above_val = line.last().unwrap().clone();
+    let above_val = *line.last().unwrap();
     let below_val = solve_line_part1(diff_line(line));
     return above_val + below_val;
 }
 
-fn part1(input: &str) -> Int {
-    solve(input, solve_line_part1)
-}
-
 fn solve_line_part2(line: Vec<Int>) -> Int {
     if line.iter().all(|&x| x == 0) {
         return 0;
     }
-    let above_val = line.first().unwrap().clone();
+    let above_val = *line.first().unwrap();
     let below_val = solve_line_part2(diff_line(line));
     return above_val - below_val;
 }
 
-fn part2(input: &str) -> Int {
-    solve(input, solve_line_part2)
+impl Solution for Day09 {
+    type Parsed = Vec<Vec<Int>>;
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        parse::lines(input)
+            .map(|line| line.numbers(|x| ErrorKind::InvalidNumber(x.to_string())))
+            .collect()
+    }
+
+    fn part1(lines: &Self::Parsed) -> Answer {
+        solve(lines, solve_line_part1).into()
+    }
+
+    fn part2(lines: &Self::Parsed) -> Answer {
+        solve(lines, solve_line_part2).into()
+    }
 }
 
 #[cfg(test)]
@@ -64,25 +87,38 @@ mod tests {
     "#;
     #[test]
     fn part1_example() {
-        let result = part1(EXAMPLE.trim());
+        let result = Day09::part1(&Day09::parse(EXAMPLE.trim()).unwrap());
         assert_eq!(114, result);
     }
 
     #[test]
     fn part1_result() {
-        let result = part1(&resource("src/day09.txt"));
+        let result = Day09::part1(&Day09::parse(&resource("src/day09.txt")).unwrap());
         assert_eq!(1904165718, result);
     }
 
     #[test]
     fn part2_example() {
-        let result = part2(EXAMPLE.trim());
+        let result = Day09::part2(&Day09::parse(EXAMPLE.trim()).unwrap());
         assert_eq!(2, result);
     }
 
     #[test]
     fn part2_result() {
-        let result = part2(&resource("src/day09.txt"));
+        let result = Day09::part2(&Day09::parse(&resource("src/day09.txt")).unwrap());
         assert_eq!(964, result);
     }
+
+    #[test]
+    fn parse_error() {
+        let result = Day09::parse("0 3 6 9\n1 3 6 1O");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(
+                2,
+                7,
+                ErrorKind::InvalidNumber("1O".to_string())
+            ))
+        );
+    }
 }
@@ -1,50 +1,113 @@
-use std::collections::HashMap;
+use thiserror::Error;
 
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
 use crate::utils::*;
-type Int = usize;
 
-struct Map {
-    coordinates: HashMap<Point2D, char>,
-    start: Point2D,
-    bound: Point2D,
+pub struct Day10;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("unexpected tile {0:?}")]
+    UnexpectedTile(char),
+    #[error("missing start tile 'S'")]
+    MissingStart,
+    #[error("the pipes from 'S' don't lead around a loop back to it")]
+    OpenLoop,
+    #[error(transparent)]
+    UnevenRow(#[from] UnevenRow),
+    #[error(transparent)]
+    EmptyGrid(#[from] EmptyGrid),
 }
-impl Map {
-    fn new(text: &str) -> Map {
-        let mut coordinates = HashMap::<Point2D, char>::new();
-        let mut start = Point2D::new(0, 0);
-
-        let lines: Vec<&str> = text.split('\n').collect();
-        for y in 0..lines.len() {
-            for x in 0..lines[y].len() {
-                let ch = lines[y].chars().nth(x).unwrap();
-                let pt = Point2D::new(x as i32, y as i32);
-                match ch {
-                    'S' => start = pt,
-                    '.' => continue,
-                    _ => {}
-                }
-                coordinates.insert(pt, ch);
+
+impl Hint for ErrorKind {
+    fn hint(&self) -> Option<String> {
+        match self {
+            ErrorKind::UnexpectedTile(_) => Some(
+                "the sketch is made of pipes '|-LJ7F', ground '.' and the start 'S'".to_string(),
+            ),
+            ErrorKind::OpenLoop => {
+                Some("the animal's loop is a closed chain of connected pipes".to_string())
             }
+            ErrorKind::MissingStart | ErrorKind::UnevenRow(_) | ErrorKind::EmptyGrid(_) => None,
         }
+    }
+}
 
-        return Map {
-            coordinates,
-            start,
-            bound: Point2D::new(lines[0].len() as i32, lines.len() as i32),
+pub struct Map {
+    tiles: Grid<char>,
+    start: Point2D,
+    /// the tiles of the loop through the start, in order
+    path: Vec<Point2D>,
+}
+impl Map {
+    fn new(text: &str) -> Result<Map, ParseError<ErrorKind>> {
+        let (tiles, starts) = Grid::parse_marked(text, Some('S'), |ch| {
+            match Map::connections(ch, &Point2D::zero()) {
+                Some(_) => Ok(ch),
+                None => Err(ErrorKind::UnexpectedTile(ch)),
+            }
+        })?;
+        let mut map = Map {
+            tiles,
+            start: *starts
+                .first()
+                .ok_or(ParseError::new(1, 1, ErrorKind::MissingStart))?,
+            path: vec![],
         };
+        map.path = map.loop_path().ok_or_else(|| {
+            // the grid drops blank lines, find the 'S' in the text to point at it
+            let (line, column) = text
+                .lines()
+                .enumerate()
+                .find_map(|(n, line)| {
+                    line.chars()
+                        .position(|ch| ch == 'S')
+                        .map(|x| (n + 1, x + 1))
+                })
+                .unwrap_or((1, 1));
+            return ParseError::new(line, column, ErrorKind::OpenLoop);
+        })?;
+        return Ok(map);
+    }
+
+    /// walks from the start along pipes that connect to each other, none if the walk runs
+    /// into ground or a pipe that doesn't connect back before it returns to the start. every
+    /// pipe connects two tiles, so a walk like that can't get stuck in a loop without the start
+    fn loop_path(&self) -> Option<Vec<Point2D>> {
+        let connected = |from: &Point2D, to: &Point2D| self.next(from).contains(to);
+        let mut path = vec![self.start];
+        let mut previous = self.start;
+        let mut current = Direction::ALL
+            .iter()
+            .map(|&direction| self.start.translate(direction))
+            .find(|p| connected(p, &self.start))?;
+        while current != self.start {
+            path.push(current);
+            let next = self
+                .next(&current)
+                .into_iter()
+                .find(|&p| p != previous)
+                .filter(|p| *p == self.start || connected(p, &current))?;
+            previous = current;
+            current = next;
+        }
+        return Some(path);
     }
 
     fn at(&self, position: &Point2D) -> char {
-        return match self.coordinates.get(position) {
-            Some(&c) => c,
-            None => '.',
-        };
+        return self.tiles.get(*position).copied().unwrap_or('.');
     }
 
     fn next(&self, from: &Point2D) -> Vec<Point2D> {
+        return Self::connections(self.at(from), from).expect("tiles are checked by Map::new");
+    }
+
+    /// the positions a `tile` at `from` connects to, none if it isn't a tile at all
+    fn connections(tile: char, from: &Point2D) -> Option<Vec<Point2D>> {
         let mut result = Vec::<Point2D>::new();
-        let current = self.at(from);
-        match current {
+        match tile {
             // - is a horizontal pipe connecting east and west.
             '-' => {
                 result.push(Self::east(from));
@@ -77,11 +140,11 @@ impl Map {
             }
             'S' => {}
             '.' => {}
-            x => panic!("unexpected: '{}'", x as char),
+            _ => return None,
             // S is the starting position of the animal; there is a pipe on this tile, but your sketch doesn't show what shape the pipe has.
             // . is ground; there is no pipe in this tile.
         }
-        return result;
+        return Some(result);
     }
     fn west(p: &Point2D) -> Point2D {
         p + Point2D::new(-1, 0)
@@ -97,58 +160,23 @@ impl Map {
     }
 }
 
-fn solve(input: &str) -> (Int, Int) {
-    let map = &Map::new(input);
-    let mut loop_coordinates = HashMap::<Point2D, char>::new();
-    let mut explore = vec![
-        Map::north(&map.start),
-        Map::south(&map.start),
-        Map::east(&map.start),
-        Map::west(&map.start),
-    ]
-    .into_iter()
-    .filter(|p| map.next(p).iter().any(|&s| s == map.start))
-    .collect::<Vec<Point2D>>();
-
-    while explore.len() > 0 {
-        let cur = explore.pop().unwrap();
-        for next in map.next(&cur) {
-            if !loop_coordinates.contains_key(&next) {
-                loop_coordinates.insert(next, map.at(&next));
-                explore.push(next);
-            }
-        }
-    }
-    let part1 = loop_coordinates.len() / 2;
-
-    let map = Map {
-        bound: map.bound,
-        coordinates: loop_coordinates,
-        start: map.start,
-    };
-    let mut cnt = 0;
-    for y in 0..map.bound.y {
-        let mut pipe_crossings = 0;
-        for x in 0..map.bound.x {
-            let pos = Point2D::new(x, y);
-            let ch = map.at(&pos);
-            if ch == '.' && pipe_crossings % 2 == 1 {
-                cnt = cnt + 1;
-            } else if ch == '|' || ch == 'L' || ch == 'J' {
-                pipe_crossings = pipe_crossings + 1;
-            }
-        }
+impl Solution for Day10 {
+    type Parsed = Map;
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        Map::new(input)
     }
-    let part2 = cnt;
-    return (part1, part2);
-}
 
-fn part1(input: &str) -> Int {
-    solve(input).0
-}
+    fn part1(map: &Self::Parsed) -> Answer {
+        (map.path.len() / 2).into()
+    }
 
-fn part2(input: &str) -> Int {
-    solve(input).1
+    fn part2(map: &Self::Parsed) -> Answer {
+        // the loop is a polygon through the centers of its tiles, the enclosed tiles are the
+        // lattice points inside it
+        polygon::interior_points(&map.path).into()
+    }
 }
 
 #[cfg(test)]
@@ -164,26 +192,27 @@ LJ...
     "#;
     #[test]
     fn basics() {
-        let g = Map::new(EXAMPLE);
+        let g = Map::new(EXAMPLE).unwrap();
         assert_eq!(g.at(&g.start), 'S');
     }
 
     #[test]
     fn part1_example() {
-        let result = part1(EXAMPLE.trim());
+        let result = Day10::part1(&Day10::parse(EXAMPLE.trim()).unwrap());
         assert_eq!(8, result);
     }
 
     #[test]
     fn part1_result() {
-        let result = part1(&resource("src/day10.txt"));
+        let result = Day10::part1(&Day10::parse(&resource("src/day10.txt")).unwrap());
         assert_eq!(7005, result);
     }
 
     #[test]
     fn part2_example1() {
-        let result = part2(
-            r#"
+        let result = Day10::part2(
+            &Day10::parse(
+                r#"
 .F----7F7F7F7F-7....
 .|F--7||||||||FJ....
 .||.FJ||||||||L7....
@@ -195,15 +224,18 @@ L--J.L7...LJS7F-7L7.
 ....FJL-7.||.||||...
 ....L---J.LJ.LJLJ...
         "#
-            .trim(),
+                .trim(),
+            )
+            .unwrap(),
         );
         assert_eq!(8, result);
     }
 
     #[test]
     fn part2_example2() {
-        let result = part2(
-            r#"
+        let result = Day10::part2(
+            &Day10::parse(
+                r#"
 FF7FSF7F7F7F7F7F---7
 L|LJ||||||||||||F--J
 FL-7LJLJ||||||LJL-77
@@ -215,14 +247,52 @@ L---JF-JLJ.||-FJLJJ7
 L.L7LFJ|||||FJL7||LJ
 L7JLJL-JLJLJL--JLJ.L
         "#
-            .trim(),
+                .trim(),
+            )
+            .unwrap(),
         );
         assert_eq!(10, result);
     }
 
     #[test]
     fn part2_result() {
-        let result = part2(&resource("src/day10.txt"));
+        let result = Day10::part2(&Day10::parse(&resource("src/day10.txt")).unwrap());
         assert_eq!(417, result);
     }
+
+    #[test]
+    fn parse_error() {
+        let result = Day10::parse("..F7.\n.FJ|.\nSJ.L7\n|F-*J");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(4, 4, ErrorKind::UnexpectedTile('*')))
+        );
+        let result = Day10::parse("..F7.\n.FJ|.");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(1, 1, ErrorKind::MissingStart))
+        );
+    }
+
+    #[test]
+    fn parse_open_loop() {
+        // the pipes from S lead into a loop that doesn't come back
+        let result = Day10::parse("S-F7\n..LJ");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(1, 1, ErrorKind::OpenLoop))
+        );
+        // into the ground
+        let result = Day10::parse(".....\n.S-7.\n.....");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(2, 2, ErrorKind::OpenLoop))
+        );
+        // nothing connects
+        let result = Day10::parse("S.\n..");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(1, 1, ErrorKind::OpenLoop))
+        );
+    }
 }
@@ -1,50 +1,130 @@
-type Int = u64;
-type Point2D = (Int, Int);
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::{Params, Solution};
+use crate::utils::{parse, Point2D};
 use std::collections::HashSet;
+use thiserror::Error;
 
-fn solve(input: &str, expansion_rate: Int) -> Int {
-    let mut galaxies = HashSet::<Point2D>::new();
-    let lines: Vec<&str> = input.split('\n').collect();
+type Int = i64;
+
+const PART1_EXPANSION_RATE: Int = 2;
+const PART2_EXPANSION_RATE: Int = 1_000_000;
+
+pub struct Day11;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("unexpected character {0:?}, expected '.' or '#'")]
+    UnexpectedCharacter(char),
+    #[error("expected all rows to be {0} wide")]
+    RaggedRow(usize),
+}
+
+impl Hint for ErrorKind {}
+
+/// Galaxies as seen by the telescope, before accounting for the expansion of the universe
+pub struct Image {
+    galaxies: Vec<(usize, usize)>,
+    void_cols: HashSet<usize>,
+    void_rows: HashSet<usize>,
+}
+
+fn parse_image(input: &str) -> Result<Image, ParseError<ErrorKind>> {
+    let numbered: Vec<(usize, &str)> = parse::lines(input)
+        .map(|line| (line.line_no, line.text))
+        .collect();
+    let width = numbered.first().map_or(0, |(_, line)| line.len());
+    for &(line_no, line) in numbered.iter() {
+        if let Some((x, ch)) = line
+            .chars()
+            .enumerate()
+            .find(|(_, ch)| *ch != '.' && *ch != '#')
+        {
+            return Err(ParseError::new(
+                line_no,
+                x + 1,
+                ErrorKind::UnexpectedCharacter(ch),
+            ));
+        }
+        if line.len() != width {
+            let column = width.min(line.len()) + 1;
+            return Err(ParseError::new(
+                line_no,
+                column,
+                ErrorKind::RaggedRow(width),
+            ));
+        }
+    }
+    let lines: Vec<&str> = numbered.into_iter().map(|(_, line)| line).collect();
+    if lines.is_empty() {
+        return Ok(Image {
+            galaxies: vec![],
+            void_cols: HashSet::new(),
+            void_rows: HashSet::new(),
+        });
+    }
     let void_cols: HashSet<usize> = (0..lines[0].len())
         .filter(|&x| (0..lines.len()).all(|y| lines[y].chars().nth(x) == Some('.')))
         .collect();
     let void_rows: HashSet<usize> = (0..lines.len())
         .filter(|&y| lines[y].find('#').is_none())
         .collect();
-    let mut expand_y: Int = 0;
+    let mut galaxies = Vec::<(usize, usize)>::new();
     for (y, line) in lines.iter().enumerate() {
-        if void_rows.contains(&y) {
-            expand_y = expand_y + expansion_rate - 1;
-            continue;
-        }
-
-        let mut expand_x = 0;
         for (x, ch) in line.char_indices() {
-            if void_cols.contains(&x) {
-                expand_x = expand_x + expansion_rate - 1;
-            }
             if ch == '#' {
-                galaxies.insert((x as Int + expand_x, y as Int + expand_y));
+                galaxies.push((x, y));
             }
         }
     }
-    let galaxies = galaxies;
+    return Ok(Image {
+        galaxies,
+        void_cols,
+        void_rows,
+    });
+}
 
-    let mut pairs = Vec::<(Point2D, Point2D)>::new();
-    let pairq: Vec<Point2D> = galaxies.into_iter().collect();
-    for cur in 0..pairq.len() {
-        for nxt in cur + 1..pairq.len() {
-            pairs.push((pairq[cur], pairq[nxt]));
+fn solve(image: &Image, expansion_rate: Int) -> Int {
+    let expanded = |void: &HashSet<usize>, coordinate: usize| {
+        let voids_before = void.iter().filter(|&&v| v < coordinate).count() as Int;
+        coordinate as Int + voids_before * (expansion_rate - 1)
+    };
+    let galaxies: Vec<Point2D<Int>> = image
+        .galaxies
+        .iter()
+        .map(|&(x, y)| Point2D::new(expanded(&image.void_cols, x), expanded(&image.void_rows, y)))
+        .collect();
+
+    let mut pairs = Vec::<(Point2D<Int>, Point2D<Int>)>::new();
+    for cur in 0..galaxies.len() {
+        for nxt in cur + 1..galaxies.len() {
+            pairs.push((galaxies[cur], galaxies[nxt]));
         }
     }
     let pairs = pairs;
 
-    pairs
-        .iter()
-        .map(|(a, b)| {
-            (a.0 as i128 - b.0 as i128).abs() as Int + (a.1 as i128 - b.1 as i128).abs() as Int
-        })
-        .sum()
+    pairs.iter().map(|(a, b)| (a - b).manhattan_len()).sum()
+}
+
+impl Solution for Day11 {
+    type Parsed = Image;
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        parse_image(input)
+    }
+
+    fn part1(image: &Self::Parsed) -> Answer {
+        solve(image, PART1_EXPANSION_RATE).into()
+    }
+
+    fn part2(image: &Self::Parsed) -> Answer {
+        solve(image, PART2_EXPANSION_RATE).into()
+    }
+
+    fn part2_with(image: &Self::Parsed, params: &Params) -> Answer {
+        solve(image, params.get("expansion_rate", PART2_EXPANSION_RATE)).into()
+    }
 }
 
 #[cfg(test)]
@@ -66,25 +146,39 @@ mod tests {
 "#;
     #[test]
     fn part1_example() {
-        let result = solve(EXAMPLE.trim(), 2);
+        let result = Day11::part1(&Day11::parse(EXAMPLE.trim()).unwrap());
         assert_eq!(374, result);
     }
 
     #[test]
     fn part1_result() {
-        let result = solve(&resource("src/day11.txt"), 2);
+        let result = Day11::part1(&Day11::parse(&resource("src/day11.txt")).unwrap());
         assert_eq!(9591768, result);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(1030, solve(EXAMPLE.trim(), 10));
-        assert_eq!(8410, solve(EXAMPLE.trim(), 100));
+        assert_eq!(1030, solve(&Day11::parse(EXAMPLE.trim()).unwrap(), 10));
+        assert_eq!(8410, solve(&Day11::parse(EXAMPLE.trim()).unwrap(), 100));
     }
 
     #[test]
     fn part2_result() {
-        let result = solve(&resource("src/day11.txt"), 1_000_000);
-        assert_eq!(746962097860, result);
+        let result = Day11::part2(&Day11::parse(&resource("src/day11.txt")).unwrap());
+        assert_eq!(746962097860_i64, result);
+    }
+
+    #[test]
+    fn parse_error() {
+        let result = Day11::parse("...#\n.#.o");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(2, 4, ErrorKind::UnexpectedCharacter('o')))
+        );
+        let result = Day11::parse("...#\n.#.");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(2, 4, ErrorKind::RaggedRow(4)))
+        );
     }
 }
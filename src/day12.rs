@@ -1,122 +1,122 @@
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
+use crate::utils::parse::{self, Span};
+use crate::utils::{parallel, Memo};
+use thiserror::Error;
+
 type Int = u64;
 type Record = (Vec<char>, Vec<usize>);
-type Cache = std::collections::HashMap<String, Int>;
-
-fn parse_line(line: &str, expand: Int) -> Record {
-    let split: Vec<&str> = line.split(' ').collect();
-    let mut springs: String = split[0].chars().collect();
-    let mut groups: String = split[1].chars().collect();
-    for _ in 0..(expand - 1) {
-        springs.push('?');
-        springs.push_str(split[0]);
-        groups.push(',');
-        groups.push_str(split[1]);
+
+pub struct Day12;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("unexpected spring {0:?}, expected '.', '#' or '?'")]
+    UnexpectedSpring(char),
+    #[error("expected '<springs> <groups>'")]
+    MissingGroups,
+    #[error("invalid group size '{0}'")]
+    InvalidGroup(String),
+}
+
+impl Hint for ErrorKind {
+    fn hint(&self) -> Option<String> {
+        Some("records look like '???.### 1,1,3'".to_string())
     }
-    return (
-        springs.chars().collect(),
-        groups
-            .split(',')
-            .map(|x| x.parse::<usize>().expect("not a int"))
-            .collect(),
-    );
 }
 
-fn solve_rec(spring: &Vec<char>, groups: &Vec<usize>, cache: &mut Cache) -> Int {
-    let mut key: String = "".to_string();
-    key.push_str(spring.iter().map(|&x| x).collect::<String>().as_str());
-    key.push(' ');
-    key.push_str(
-        groups
-            .iter()
-            .map(|&x| format!("{},", x))
-            .collect::<String>()
-            .as_str(),
-    );
-
-    return match cache.get(&key) {
-        Some(&x) => x,
-        None => {
-            let result = count_rec(spring, groups, cache);
-            cache.insert(key, result);
-            result
-        }
-    };
+fn parse_line(line: Span) -> Result<Record, ParseError<ErrorKind>> {
+    let (springs, groups) = line
+        .split_once(" ")
+        .ok_or(line.error_after(ErrorKind::MissingGroups))?;
+    springs.check_chars(|ch| ".#?".contains(ch), ErrorKind::UnexpectedSpring)?;
+    let groups = groups.list(",", |x| ErrorKind::InvalidGroup(x.to_string()))?;
+    return Ok((springs.text.chars().collect(), groups));
 }
 
-fn count_rec(springs: &Vec<char>, groups: &Vec<usize>, cache: &mut Cache) -> Int {
-    match (groups.first(), springs.first()) {
-        (None, _) => match springs.iter().find(|&c| *c == '#') {
-            None => 1,
-            Some(_) => 0,
-        },
-        (_, None) => 0, // no more springs to match, although we still have groups to match
-        (_, Some('.')) => {
-            let dots_trimmed = springs
-                .iter()
-                .skip_while(|&c| *c == '.')
-                .map(|&c| c)
-                .collect();
-            solve_rec(&dots_trimmed, groups, cache)
-        }
-        (_, Some('?')) => {
-            let mut right = springs.clone();
-            right[0] = '.';
-            let mut left = springs.clone();
-            left[0] = '#';
-            solve_rec(&left, &groups, cache) + solve_rec(&right, &groups, cache)
-        }
-        (Some(0), Some('#')) => 0,
-        (Some(&group_size), Some('#')) => {
-            if springs.len() < group_size {
-                return 0;
-            }
+fn unfold((springs, groups): &Record, times: usize) -> Record {
+    let mut unfolded_springs = springs.clone();
+    let mut unfolded_groups = groups.clone();
+    for _ in 0..(times - 1) {
+        unfolded_springs.push('?');
+        unfolded_springs.extend(springs);
+        unfolded_groups.extend(groups);
+    }
+    return (unfolded_springs, unfolded_groups);
+}
 
-            if springs
-                .iter()
-                .take(group_size)
-                .find(|&c| *c == '.')
-                .is_some()
-            {
-                return 0;
-            }
+/// the remaining springs and groups are always a suffix of the record, their lengths identify them
+type Cache = Memo<(usize, usize), Int>;
 
-            let groups_remaining: Vec<usize> = groups[1..].into();
-            if groups_remaining.len() > 0 {
-                // if the group is followed by a '#' the counts are off
-                if springs.len() < group_size + 1 || springs[group_size] == '#' {
-                    return 0;
-                }
-                // it needs to be followed by a '?' or '.'
-                // we can skip that character, too
-                let springs = springs[group_size + 1..].into();
-                solve_rec(&springs, &groups_remaining, cache)
-            } else {
-                let tail = &springs[group_size..].into();
-                solve_rec(&tail, &groups_remaining, cache)
+fn count_arrangements(springs: &[char], groups: &[usize], cache: &mut Cache) -> Int {
+    cache.get_or((springs.len(), groups.len()), |cache| {
+        match (groups.first(), springs.first()) {
+            (None, _) => match springs.iter().find(|&c| *c == '#') {
+                None => 1,
+                Some(_) => 0,
+            },
+            (_, None) => 0, // no more springs to match, although we still have groups to match
+            (_, Some('.')) => count_arrangements(&springs[1..], groups, cache),
+            (_, Some('?')) => {
+                // either a '.' or the start of the next group
+                count_arrangements(&springs[1..], groups, cache)
+                    + place_group(springs, groups, cache)
             }
+            (_, Some(_)) => place_group(springs, groups, cache),
         }
-        _ => panic!("Invalid input"),
+    })
+}
+
+/// the arrangements with the first group starting at the first spring
+fn place_group(springs: &[char], groups: &[usize], cache: &mut Cache) -> Int {
+    let group_size = groups[0];
+    if group_size == 0 || springs.len() < group_size {
+        return 0;
     }
+
+    if springs.iter().take(group_size).any(|&c| c == '.') {
+        return 0;
+    }
+
+    let groups_remaining = &groups[1..];
+    if springs.len() == group_size {
+        return count_arrangements(&[], groups_remaining, cache);
+    }
+    // if the group is followed by a '#' the counts are off
+    if springs[group_size] == '#' {
+        return 0;
+    }
+    // it needs to be followed by a '?' or '.'
+    // we can skip that character, too
+    count_arrangements(&springs[group_size + 1..], groups_remaining, cache)
 }
 
-fn part1(input: &str) -> Int {
-    let mut cache = Cache::new();
-    input
-        .trim()
-        .split('\n')
-        .map(|ln| parse_line(ln, 1))
-        .map(|(chars, counts)| solve_rec(&chars, &counts, &mut cache))
-        .sum()
+fn arrangements((springs, groups): &Record) -> Int {
+    count_arrangements(springs, groups, &mut Cache::new())
 }
 
-fn part2(input: &str) -> Int {
-    let mut cache = Cache::new();
-    input
-        .trim()
-        .split('\n')
-        .map(|ln| parse_line(ln, 5))
-        .map(|(chars, counts)| solve_rec(&chars, &counts, &mut cache))
-        .sum()
+impl Solution for Day12 {
+    type Parsed = Vec<Record>;
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        parse::lines(input).map(parse_line).collect()
+    }
+
+    fn part1(records: &Self::Parsed) -> Answer {
+        parallel::map(records, arrangements)
+            .into_iter()
+            .sum::<Int>()
+            .into()
+    }
+
+    fn part2(records: &Self::Parsed) -> Answer {
+        parallel::map(records, |record| arrangements(&unfold(record, 5)))
+            .into_iter()
+            .sum::<Int>()
+            .into()
+    }
 }
 
 #[cfg(test)]
@@ -133,25 +133,43 @@ mod tests {
         "#;
     #[test]
     fn part1_example() {
-        let result = part1(EXAMPLE);
+        let result = Day12::part1(&Day12::parse(EXAMPLE).unwrap());
         assert_eq!(21, result);
     }
 
     #[test]
     fn part1_result() {
-        let result = part1(&resource("src/day12.txt"));
+        let result = Day12::part1(&Day12::parse(&resource("src/day12.txt")).unwrap());
         assert_eq!(7771, result);
     }
 
     #[test]
     fn part2_example() {
-        let result = part2(EXAMPLE.trim());
+        let result = Day12::part2(&Day12::parse(EXAMPLE.trim()).unwrap());
         assert_eq!(525152, result);
     }
 
     #[test]
     fn part2_result() {
-        let result = part2(&resource("src/day12.txt"));
-        assert_eq!(10861030975833, result);
+        let result = Day12::part2(&Day12::parse(&resource("src/day12.txt")).unwrap());
+        assert_eq!(10861030975833_i64, result);
+    }
+
+    #[test]
+    fn parse_error() {
+        let result = Day12::parse("???.### 1,1,3\n.??..??...?##. 1,x,3");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(
+                2,
+                18,
+                ErrorKind::InvalidGroup("x".to_string())
+            ))
+        );
+        let result = Day12::parse("???.*## 1,1,3");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(1, 5, ErrorKind::UnexpectedSpring('*')))
+        );
     }
 }
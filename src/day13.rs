@@ -1,15 +1,33 @@
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
+use crate::utils::{parallel, parse};
+use thiserror::Error;
+
 type Int = usize;
 type Mirror = (Vec<String>, Vec<String>);
 
-fn find_reflection(input: &Vec<String>, skip: Option<Int>) -> Option<Int> {
+pub struct Day13;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("unexpected character {0:?}, expected '.' or '#'")]
+    UnexpectedCharacter(char),
+    #[error("expected all rows of a pattern to be {0} wide")]
+    RaggedRow(usize),
+}
+
+impl Hint for ErrorKind {}
+
+fn find_reflection(input: &[String], skip: Option<Int>) -> Option<Int> {
     for candidate in 1..input.len() {
         let mut low: isize = candidate as isize - 1;
         let mut high = candidate;
         let mut all_matches = low >= 0;
         while low >= 0 && high < input.len() && all_matches {
             all_matches = all_matches && input[low as usize] == input[high];
-            low = low - 1;
-            high = high + 1;
+            low -= 1;
+            high += 1;
         }
         if all_matches {
             let result = Some(candidate);
@@ -21,22 +39,21 @@ fn find_reflection(input: &Vec<String>, skip: Option<Int>) -> Option<Int> {
     return None;
 }
 
-fn parse_input(input: &str) -> Vec<Mirror> {
+fn parse_input(input: &str) -> Result<Vec<Mirror>, ParseError<ErrorKind>> {
     let mut mirrors = Vec::<Mirror>::new();
-    let mut lines = Vec::<String>::new();
-    let mut lines_cnt = 0;
-    let mut line_len = 0;
-    for line in input.split('\n').map(|x| x.trim()) {
-        if line.len() > 0 {
-            lines.push(line.to_string());
-            line_len = line.len();
-            lines_cnt = lines_cnt + 1;
-            continue;
-        }
-        if lines_cnt == 0 {
-            continue;
+    for section in parse::sections(input) {
+        let mut lines = Vec::<String>::new();
+        for line in section.iter().map(|line| line.trim()) {
+            line.check_chars(|ch| ch == '.' || ch == '#', ErrorKind::UnexpectedCharacter)?;
+            if let Some(line_len) = lines.first().map(|first| first.len()) {
+                if line.text.len() != line_len {
+                    return Err(line.skip(line_len).error(ErrorKind::RaggedRow(line_len)));
+                }
+            }
+            lines.push(line.text.to_string());
         }
-        // we have a bunch of lines queue up and we've reched an empty line
+
+        let line_len = lines[0].len();
         let mut columns = Vec::<String>::new();
         for c in 0..line_len {
             let mut column_str = "".to_string();
@@ -46,12 +63,9 @@ fn parse_input(input: &str) -> Vec<Mirror> {
             columns.push(column_str.to_string());
         }
 
-        mirrors.push((lines.clone(), columns));
-        lines.clear();
-        line_len = 0;
-        lines_cnt = 0;
+        mirrors.push((lines, columns));
     }
-    return mirrors;
+    return Ok(mirrors);
 }
 
 fn flip_at(input: &String, index: usize) -> String {
@@ -73,7 +87,7 @@ fn flip_at(input: &String, index: usize) -> String {
         .collect()
 }
 
-fn replace_at(input: &Vec<String>, with: &String, at: usize) -> Vec<String> {
+fn replace_at(input: &[String], with: &String, at: usize) -> Vec<String> {
     input
         .iter()
         .enumerate()
@@ -87,35 +101,31 @@ fn replace_at(input: &Vec<String>, with: &String, at: usize) -> Vec<String> {
         .collect()
 }
 
-fn part1(input: &str) -> Int {
-    parse_input(input)
-        .iter()
-        .map(|(lines, columns)| {
-            find_reflection(&columns, None)
-                .or(find_reflection(&lines, None).map(|h| h * 100))
-                .expect("expecting exactly one reflection")
-        })
-        .sum()
+fn summarize(mirror: &Mirror) -> Int {
+    let (lines, columns) = mirror;
+    find_reflection(columns, None)
+        .or(find_reflection(lines, None).map(|h| h * 100))
+        .expect("expecting exactly one reflection")
 }
 
 fn with_smudge_fixed(mirror: &Mirror) -> Int {
     let (lines, columns) = mirror;
-    let except_horizontal = find_reflection(&lines, None);
-    let except_vertical = find_reflection(&columns, None);
+    let except_horizontal = find_reflection(lines, None);
+    let except_vertical = find_reflection(columns, None);
     for y in 0..lines.len() {
         for x in 0..lines[y].len() {
             let smudged_line = flip_at(&lines[y], x);
-            let smudged_line_fixed = replace_at(&lines, &smudged_line, y);
+            let smudged_line_fixed = replace_at(lines, &smudged_line, y);
             let found_horizontal = find_reflection(&smudged_line_fixed, except_horizontal);
-            if found_horizontal.is_some() {
-                return found_horizontal.unwrap() * 100;
+            if let Some(horizontal) = found_horizontal {
+                return horizontal * 100;
             }
 
             let smudged_column = flip_at(&columns[x], y);
-            let smudged_column_fixed = replace_at(&columns, &smudged_column, x);
+            let smudged_column_fixed = replace_at(columns, &smudged_column, x);
             let found_vertical = find_reflection(&smudged_column_fixed, except_vertical);
-            if found_vertical.is_some() {
-                return found_vertical.unwrap();
+            if let Some(vertical) = found_vertical {
+                return vertical;
             }
         }
     }
@@ -123,8 +133,27 @@ fn with_smudge_fixed(mirror: &Mirror) -> Int {
     panic!("expecting exactly one smudge");
 }
 
-fn part2(input: &str) -> Int {
-    parse_input(input).iter().map(with_smudge_fixed).sum()
+impl Solution for Day13 {
+    type Parsed = Vec<Mirror>;
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        parse_input(input)
+    }
+
+    fn part1(mirrors: &Self::Parsed) -> Answer {
+        parallel::map(mirrors, summarize)
+            .into_iter()
+            .sum::<Int>()
+            .into()
+    }
+
+    fn part2(mirrors: &Self::Parsed) -> Answer {
+        parallel::map(mirrors, with_smudge_fixed)
+            .into_iter()
+            .sum::<Int>()
+            .into()
+    }
 }
 
 #[cfg(test)]
@@ -150,25 +179,39 @@ mod tests {
 "#;
     #[test]
     fn part1_example() {
-        let result = part1(EXAMPLE);
+        let result = Day13::part1(&Day13::parse(EXAMPLE).unwrap());
         assert_eq!(405, result);
     }
 
     #[test]
     fn part1_result() {
-        let result = part1(&resource("src/day13.txt"));
+        let result = Day13::part1(&Day13::parse(&resource("src/day13.txt")).unwrap());
         assert_eq!(29846, result);
     }
 
     #[test]
     fn part2_example() {
-        let result = part2(EXAMPLE);
+        let result = Day13::part2(&Day13::parse(EXAMPLE).unwrap());
         assert_eq!(400, result);
     }
 
     #[test]
     fn part2_result() {
-        let result = part2(&resource("src/day13.txt"));
+        let result = Day13::part2(&Day13::parse(&resource("src/day13.txt")).unwrap());
         assert_eq!(25401, result);
     }
+
+    #[test]
+    fn parse_error() {
+        let result = Day13::parse("#.##..##.\n..#.##.#.\n##....O#.\n");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(3, 7, ErrorKind::UnexpectedCharacter('O')))
+        );
+        let result = Day13::parse("#.##..##.\n..#.##.\n");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(2, 8, ErrorKind::RaggedRow(9)))
+        );
+    }
 }
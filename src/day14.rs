@@ -1,46 +1,51 @@
 type Int = usize;
-use crate::utils::Point2D;
-use std::collections::HashMap;
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
+use crate::utils::{cycle, EmptyGrid, Grid, Point2D, UnevenRow};
+use thiserror::Error;
+
+pub struct Day14;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("unexpected character {0:?}, expected '.', '#' or 'O'")]
+    UnexpectedCharacter(char),
+    #[error(transparent)]
+    UnevenRow(#[from] UnevenRow),
+    #[error(transparent)]
+    EmptyGrid(#[from] EmptyGrid),
+}
+
+impl Hint for ErrorKind {}
 
-type Map = HashMap<Point2D, Stone>;
+type Map = Grid<Option<Stone>>;
 
 #[derive(Hash, Eq, PartialEq, Debug, Copy, Clone)]
-enum Stone {
+pub enum Stone {
     Fixed,
     Rolling,
 }
 
-fn parse_input(input: &str) -> (Point2D, Map) {
-    let mut result = Map::new();
-    let mut bounds = Point2D::new(0, 0);
-    for (y, line) in input.split('\n').enumerate() {
-        for (x, ch) in line.char_indices() {
-            let point = Point2D::new(x as i32, y as i32);
-            match ch {
-                '.' => {}
-                '#' => {
-                    result.insert(point, Stone::Fixed);
-                }
-                'O' => {
-                    result.insert(point, Stone::Rolling);
-                }
-                c => panic!("unexpected character {}", c),
-            };
-            bounds = point;
-        }
-    }
-    return (bounds + Point2D::new(1, 1), result);
+fn parse_input(input: &str) -> Result<Map, ParseError<ErrorKind>> {
+    return Grid::parse(input, |ch| match ch {
+        '.' => Ok(None),
+        '#' => Ok(Some(Stone::Fixed)),
+        'O' => Ok(Some(Stone::Rolling)),
+        c => Err(ErrorKind::UnexpectedCharacter(c)),
+    });
 }
 
-fn tilt_north(bounds: &Point2D, map: &mut Map) {
+fn tilt_north(map: &mut Map) {
+    let bounds = map.bounds();
     for y in 0..bounds.y {
         for x in 0..bounds.x {
             let p = Point2D::new(x, y);
-            if map.get(&p).is_none() {
+            if map[p].is_none() {
                 // since we start at y 0, we don't need to look above, we just need to look bellow, if there's something we can roll into this position
                 for z in y + 1..bounds.y {
                     let p_next = Point2D::new(x, z);
-                    match map.get(&p_next) {
+                    match map[p_next] {
                         None => {
                             continue; // next
                         }
@@ -48,8 +53,8 @@ fn tilt_north(bounds: &Point2D, map: &mut Map) {
                             break; // no need to look past it, we're done
                         }
                         Some(Stone::Rolling) => {
-                            map.remove(&p_next);
-                            map.insert(p, Stone::Rolling);
+                            map[p_next] = None;
+                            map[p] = Some(Stone::Rolling);
                             // roll the stone north!
                             break;
                         }
@@ -60,84 +65,53 @@ fn tilt_north(bounds: &Point2D, map: &mut Map) {
     }
 }
 
-fn score(bounds: &Point2D, map: &Map) -> Int {
+fn score(map: &Map) -> Int {
+    let height = map.height();
     return map
         .iter()
         .filter_map(|(position, &stone)| match stone {
-            Stone::Fixed => None,
-            Stone::Rolling => Some(bounds.y - position.y),
+            Some(Stone::Rolling) => Some(height - position.y as usize),
+            _ => None,
         })
-        .sum::<i32>() as Int;
+        .sum();
 }
 
-fn part1(input: &str) -> Int {
-    let (bounds, mut map) = parse_input(input);
-    tilt_north(&bounds, &mut map);
-    return score(&bounds, &map);
-}
+const CYCLES: usize = 1000000000;
 
-fn rotate_clockwise(bounds: &mut Point2D, map: &mut Map) {
-    let stones = map
-        .iter()
-        .map(|(&pos, &stone)| (pos, stone))
-        .collect::<Vec<(Point2D, Stone)>>();
-    let old_bounds = bounds.clone();
-    bounds.x = old_bounds.y;
-    bounds.y = old_bounds.x;
-
-    map.clear();
-    for (p, s) in stones {
-        let y_ = p.x;
-        let x_ = old_bounds.y - p.y - 1;
-        let new_position = Point2D::new(x_, y_);
-        map.insert(new_position, s);
+/// a cycle is tilting north, west, south, east
+fn spin(map: &Map) -> Map {
+    let mut map = map.clone();
+    // instead of implementing each direction we can rotate and tilt north for each direction, untill we make a full circle
+    for _ in &["N", "W", "S", "E"] {
+        tilt_north(&mut map);
+        map = map.rotate_clockwise();
     }
+    return map;
 }
 
-fn hash_key<'a>(bounds: &'a Point2D, map: &'a Map, s: &'a mut String) {
-    for y in 0..bounds.y {
-        for x in 0..bounds.x {
-            s.push(match map.get(&Point2D::new(x, y)) {
-                None => '.',
-                Some(Stone::Fixed) => '#',
-                Some(Stone::Rolling) => 'O',
-            });
-        }
-        s.push('\n');
-    }
+fn spin_cycles(map: &Map) -> Int {
+    // the board ends up repeating itself long before running all the cycles
+    let history = cycle::simulate(map.clone(), spin, CYCLES);
+    score(history.nth(CYCLES).expect("simulated until a repeat"))
 }
-const CYCLES: usize = 1000000000;
-fn part2(input: &str) -> Int {
-    let (mut bounds, mut map) = parse_input(input);
-    let mut c = 0;
-    let mut cache = HashMap::<String, usize>::new();
-
-    while c < CYCLES {
-        let mut key = String::new();
-        hash_key(&bounds, &map, &mut key);
-
-        let cached = *cache.entry(key).or_insert(c.clone());
-        if cached < c {
-            let increment = c - cached;
-            let remaining = CYCLES - c;
-            let increment = increment * (remaining / increment);
-            c += increment;
-
-            // panic!("hit loop at {c}, seen before at {cached}. Increasing c to {next}");
-            if increment > 0 {
-                continue;
-            }
-        }
 
-        // a cycle is tilting north, west, south, east
-        // instead of implementing each direction we can rotate and tilt north for each direction, untill we make a full circle
-        for _ in &["N", "W", "S", "E"] {
-            tilt_north(&bounds, &mut map);
-            rotate_clockwise(&mut bounds, &mut map);
-        }
-        c += 1;
+impl Solution for Day14 {
+    type Parsed = Map;
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        parse_input(input)
+    }
+
+    fn part1(map: &Self::Parsed) -> Answer {
+        let mut map = map.clone();
+        tilt_north(&mut map);
+        return score(&map).into();
+    }
+
+    fn part2(map: &Self::Parsed) -> Answer {
+        spin_cycles(map).into()
     }
-    score(&bounds, &map)
 }
 
 #[cfg(test)]
@@ -145,9 +119,7 @@ mod tests {
     use super::*;
     use crate::utils;
 
-    #[test]
-    fn part1_example() {
-        let example = r#"
+    const EXAMPLE: &str = r#"
 O....#....
 O.OO#....#
 .....##...
@@ -158,42 +130,68 @@ O.#..O.#.#
 .......O..
 #....###..
 #OO..#....
-"#
-        .trim();
-        let result = part1(example);
+"#;
+
+    #[test]
+    fn part1_example() {
+        let result = Day14::part1(&Day14::parse(EXAMPLE).unwrap());
         assert_eq!(result, 136)
     }
 
+    fn render(map: &Map) -> String {
+        return map.render(|stone| match stone {
+            None => '.',
+            Some(Stone::Fixed) => '#',
+            Some(Stone::Rolling) => 'O',
+        });
+    }
+
+    #[test]
+    fn tilt_north_example() {
+        let mut map = Day14::parse(EXAMPLE).unwrap();
+        assert_eq!(render(&map), EXAMPLE.trim_start());
+        tilt_north(&mut map);
+        let tilted = r#"
+OOOO.#.O..
+OO..#....#
+OO..O##..O
+O..#.OO...
+........#.
+..#....#.#
+..O..#.O.O
+..O.......
+#....###..
+#....#....
+"#;
+        assert_eq!(render(&map), tilted.trim_start());
+    }
+
     #[test]
     fn part1_result() {
         let input = utils::resource("src/day14.txt");
-        let result = part1(&input);
+        let result = Day14::part1(&Day14::parse(&input).unwrap());
         assert_eq!(result, 105982);
     }
 
     #[test]
     fn part2_example() {
-        let example = r#"
-O....#....
-O.OO#....#
-.....##...
-OO.#O....O
-.O.....O#.
-O.#..O.#.#
-..O..#O..O
-.......O..
-#....###..
-#OO..#....
-"#
-        .trim();
-        let result = part2(example);
+        let result = Day14::part2(&Day14::parse(EXAMPLE).unwrap());
         assert_eq!(result, 64)
     }
 
     #[test]
     fn part2_result() {
         let input = utils::resource("src/day14.txt");
-        let result = part2(&input);
+        let result = Day14::part2(&Day14::parse(&input).unwrap());
         assert_eq!(result, 85175);
     }
+
+    #[test]
+    fn parse_error() {
+        let result = Day14::parse("O....#....\nO.OO#..#0.");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(2, 9, ErrorKind::UnexpectedCharacter('0')))
+        );
+    }
 }
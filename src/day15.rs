@@ -1,4 +1,38 @@
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
+use crate::utils::parse::{self, Span};
 use std::collections::HashMap;
+use thiserror::Error;
+
+pub struct Day15;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("expected '<label>-' or '<label>=<focal length>' in '{0}'")]
+    MissingOperation(String),
+    #[error("invalid focal length '{0}'")]
+    InvalidFocalLength(String),
+}
+
+impl Hint for ErrorKind {
+    fn hint(&self) -> Option<String> {
+        Some("steps look like 'rn=1' or 'cm-', separated by ','".to_string())
+    }
+}
+
+fn parse_step(step: Span) -> Result<String, ParseError<ErrorKind>> {
+    let invalid_step = || step.error(ErrorKind::MissingOperation(step.text.to_string()));
+    match step.text.find(['-', '=']) {
+        Some(i) if &step.text[i..] == "-" => {}
+        Some(i) if &step.text[i..i + 1] == "=" => {
+            step.skip(i + 1)
+                .parse::<u8, _, _>(|x| ErrorKind::InvalidFocalLength(x.to_string()))?;
+        }
+        _ => return Err(invalid_step()),
+    }
+    return Ok(step.text.to_string());
+}
 
 fn hash<'a>(chars: std::str::Chars<'a>) -> u8 {
     let mut current: usize = 0;
@@ -10,13 +44,6 @@ fn hash<'a>(chars: std::str::Chars<'a>) -> u8 {
     current as u8
 }
 
-fn part1(input: &str) -> u128 {
-    input
-        .split(',')
-        .map(|part| hash(part.chars()) as u128)
-        .sum()
-}
-
 type LabeledLens = (String, u8);
 type LenseBox = Vec<LabeledLens>;
 trait LenseBoxContentFinder {
@@ -30,11 +57,10 @@ impl LenseBoxContentFinder for &LenseBox {
     }
 }
 
-fn part2(input: &str) -> u128 {
-    let instructions = input.split(',');
+fn focusing_power(instructions: &[String]) -> u128 {
     let mut boxes = HashMap::<u8, LenseBox>::new();
     for inst in instructions {
-        let op_index = inst.find(&['-', '=']).expect("operation not found");
+        let op_index = inst.find(['-', '=']).expect("operation not found");
         let lens_label = &inst[0..op_index];
         let box_id = hash(lens_label.chars());
         match inst.as_bytes()[op_index] {
@@ -82,6 +108,33 @@ fn part2(input: &str) -> u128 {
     return result;
 }
 
+impl Solution for Day15 {
+    type Parsed = Vec<String>;
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        let mut steps = Vec::<String>::new();
+        for line in parse::lines(input) {
+            for step in line.split(",") {
+                steps.push(parse_step(step)?);
+            }
+        }
+        return Ok(steps);
+    }
+
+    fn part1(steps: &Self::Parsed) -> Answer {
+        steps
+            .iter()
+            .map(|part| hash(part.chars()) as u128)
+            .sum::<u128>()
+            .into()
+    }
+
+    fn part2(steps: &Self::Parsed) -> Answer {
+        focusing_power(steps).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,27 +143,49 @@ mod tests {
     const EXAMPLE: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
     #[test]
     fn part1_example() {
-        let result = part1(EXAMPLE);
+        let result = Day15::part1(&Day15::parse(EXAMPLE).unwrap());
         assert_eq!(result, 1320)
     }
 
     #[test]
     fn part1_result() {
         let input = utils::resource("src/day15.txt");
-        let result = part1(&input);
+        let result = Day15::part1(&Day15::parse(&input).unwrap());
         assert_eq!(result, 514281);
     }
 
     #[test]
     fn part2_example() {
-        let result = part2(EXAMPLE);
+        let result = Day15::part2(&Day15::parse(EXAMPLE).unwrap());
         assert_eq!(result, 145)
     }
 
     #[test]
     fn part2_result() {
         let input = utils::resource("src/day15.txt");
-        let result = part2(&input);
+        let result = Day15::part2(&Day15::parse(&input).unwrap());
         assert_eq!(result, 244199);
     }
+
+    #[test]
+    fn parse_error() {
+        let result = Day15::parse("rn=1,cm-,qp=3,cm=x");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(
+                1,
+                18,
+                ErrorKind::InvalidFocalLength("x".to_string())
+            ))
+        );
+        let result = Day15::parse("rn=1,cm");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(
+                1,
+                6,
+                ErrorKind::MissingOperation("cm".to_string())
+            ))
+        );
+    }
 }
@@ -1,10 +1,39 @@
-use crate::utils::{Direction, Point2D};
-use std::collections::{HashMap, HashSet};
-type Map = HashMap<Point2D, char>;
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
+use crate::utils::{parallel, Direction, EmptyGrid, Grid, Point2D, UnevenRow};
+use std::collections::HashSet;
+use thiserror::Error;
+type Map = Grid<char>;
 type Beam = (Point2D, Direction);
 
-fn direct_beam(tile_type: char, incoming_direction: Direction) -> Vec<Direction> {
-    match tile_type {
+pub struct Day16;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("unexpected tile {0:?}")]
+    UnexpectedTile(char),
+    #[error(transparent)]
+    UnevenRow(#[from] UnevenRow),
+    #[error(transparent)]
+    EmptyGrid(#[from] EmptyGrid),
+}
+
+impl Hint for ErrorKind {
+    fn hint(&self) -> Option<String> {
+        match self {
+            ErrorKind::UnexpectedTile(_) => Some(
+                r"the contraption is made of empty space '.', mirrors '/' '\' and splitters '|' '-'"
+                    .to_string(),
+            ),
+            ErrorKind::UnevenRow(_) | ErrorKind::EmptyGrid(_) => None,
+        }
+    }
+}
+
+/// where a beam goes after hitting a tile, none if it isn't a tile at all
+fn direct_beam(tile_type: char, incoming_direction: Direction) -> Option<Vec<Direction>> {
+    let directions = match tile_type {
         '.' => vec![incoming_direction],
         // mirrors
         '/' => match incoming_direction {
@@ -32,25 +61,23 @@ fn direct_beam(tile_type: char, incoming_direction: Direction) -> Vec<Direction>
             Direction::Down => vec![Direction::Down],
             Direction::Left => vec![Direction::Up, Direction::Down],
         },
-        c => panic!("expected tile, got '{}'", c),
-    }
+        _ => return None,
+    };
+    return Some(directions);
 }
 
-fn parse_input(input: &str) -> Map {
-    let mut map = Map::new();
-    for (y, line) in input.trim().split('\n').enumerate() {
-        for (x, ch) in line.char_indices() {
-            map.insert((x as i32, y as i32).into(), ch);
-        }
-    }
-    return map;
+fn parse_input(input: &str) -> Result<Map, ParseError<ErrorKind>> {
+    return Grid::parse(input, |ch| match direct_beam(ch, Direction::Right) {
+        Some(_) => Ok(ch),
+        None => Err(ErrorKind::UnexpectedTile(ch)),
+    });
 }
 
 fn count_energized(map: &Map, start_at: Beam) -> usize {
     let mut cur_beams = vec![start_at];
     let mut visited = HashSet::<Beam>::new();
 
-    while cur_beams.len() > 0 {
+    while !cur_beams.is_empty() {
         let mut next_beams = Vec::<Beam>::new();
 
         for beam in cur_beams {
@@ -59,14 +86,14 @@ fn count_energized(map: &Map, start_at: Beam) -> usize {
             }
 
             let (b_pos, b_dir) = beam;
-            let tile = map.get(&b_pos);
-            if tile.is_none() {
-                // out of bounds
-                continue;
-            }
+            let tile = match map.get(b_pos) {
+                Some(&tile) => tile,
+                None => continue, // out of bounds
+            };
 
             visited.insert(beam);
-            for next_direction in direct_beam(*tile.unwrap(), b_dir) {
+            let directions = direct_beam(tile, b_dir);
+            for next_direction in directions.expect("tiles are checked by parse_input") {
                 let next_position = b_pos.translate(next_direction);
                 next_beams.push((next_position, next_direction));
             }
@@ -79,16 +106,10 @@ fn count_energized(map: &Map, start_at: Beam) -> usize {
     return visited_positions.len();
 }
 
-fn part1(input: &str) -> usize {
-    count_energized(&parse_input(input), (Point2D::new(0, 0), Direction::Right))
-}
-
-fn part2(input: &str) -> usize {
-    let map = parse_input(input);
-
+fn most_energized(map: &Map) -> usize {
     let mut potential_starts = Vec::<Beam>::new();
-    let max_x = map.keys().map(|p| p.x).max().unwrap();
-    let max_y = map.keys().map(|p| p.y).max().unwrap();
+    let max_x = map.width() as i32 - 1;
+    let max_y = map.height() as i32 - 1;
     for x in 0..=max_x {
         potential_starts.push((Point2D::new(x, 0), Direction::Down));
         potential_starts.push((Point2D::new(x, max_y), Direction::Up));
@@ -98,13 +119,29 @@ fn part2(input: &str) -> usize {
         potential_starts.push((Point2D::new(max_x, y), Direction::Left));
     }
 
-    potential_starts
+    parallel::map(&potential_starts, |&b| count_energized(map, b))
         .into_iter()
-        .map(|b| count_energized(&map, b))
         .max()
         .unwrap()
 }
 
+impl Solution for Day16 {
+    type Parsed = Map;
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        parse_input(input)
+    }
+
+    fn part1(map: &Self::Parsed) -> Answer {
+        count_energized(map, (Point2D::new(0, 0), Direction::Right)).into()
+    }
+
+    fn part2(map: &Self::Parsed) -> Answer {
+        most_energized(map).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,27 +160,41 @@ mod tests {
 ..//.|....";
     #[test]
     fn part1_example() {
-        let result = part1(EXAMPLE);
+        let result = Day16::part1(&Day16::parse(EXAMPLE).unwrap());
         assert_eq!(result, 46)
     }
 
     #[test]
     fn part1_result() {
         let input = utils::resource("src/day16.txt");
-        let result = part1(&input);
+        let result = Day16::part1(&Day16::parse(&input).unwrap());
         assert_eq!(result, 7415);
     }
 
     #[test]
     fn part2_example() {
-        let result = part2(EXAMPLE);
+        let result = Day16::part2(&Day16::parse(EXAMPLE).unwrap());
         assert_eq!(result, 51)
     }
 
     #[test]
     fn part2_result() {
         let input = utils::resource("src/day16.txt");
-        let result = part2(&input);
+        let result = Day16::part2(&Day16::parse(&input).unwrap());
         assert_eq!(result, 7943);
     }
+
+    #[test]
+    fn parse_error() {
+        let result = Day16::parse(".|...\\....\n|.-.\\..#..");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(2, 8, ErrorKind::UnexpectedTile('#')))
+        );
+        let result = Day16::parse("");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(1, 1, ErrorKind::EmptyGrid(EmptyGrid)))
+        );
+    }
 }
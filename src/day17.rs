@@ -1,123 +1,114 @@
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
 use crate::utils::*;
-use std::collections::{BinaryHeap, HashMap, HashSet};
-type Map = std::collections::HashMap<Point2D, u8>;
-
-fn parse_map(input: &str) -> Map {
-    let mut result = Map::new();
-    for (y, line) in input.trim().split('\n').enumerate() {
-        for (x, ch) in line.char_indices() {
-            result.insert(
-                Point2D::new(x as i32, y as i32),
-                ch.to_digit(10).expect("needz moar digit") as u8,
-            );
-        }
-    }
-    return result;
+use thiserror::Error;
+type Map = Grid<u8>;
+
+pub struct Day17;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("expected a heat loss digit, got {0:?}")]
+    ExpectedDigit(char),
+    #[error(transparent)]
+    UnevenRow(#[from] UnevenRow),
+    #[error(transparent)]
+    EmptyGrid(#[from] EmptyGrid),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+impl Hint for ErrorKind {}
+
+fn parse_map(input: &str) -> Result<Map, ParseError<ErrorKind>> {
+    return Grid::parse(input, |ch| match ch.to_digit(10) {
+        Some(heat_loss) => Ok(heat_loss as u8),
+        None => Err(ErrorKind::ExpectedDigit(ch)),
+    });
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct Node {
     start: Point2D,
     direction: Point2D,
 }
 
-fn solve<T, I>(input: &str, next_directions: T, is_valid_solution: I) -> i32
+fn solve<T, I>(map: &Map, next_directions: T, is_valid_solution: I) -> i32
 where
     T: Fn(&Point2D) -> Vec<Point2D>,
     I: Fn(&Node) -> bool,
 {
-    let map = parse_map(input);
-    let finish = Point2D::new(
-        map.keys().map(|p| p.x).max().expect("max x"),
-        map.keys().map(|p| p.y).max().expect("max y"),
-    );
-    let mut distances = HashMap::<Node, i32>::new();
-    let mut visited = HashSet::<Node>::new();
-    let mut queue = BinaryHeap::<(i32, Node)>::new();
-
-    let right = Node {
-        start: (0, 0).into(),
-        direction: (1, 0).into(),
-    };
-    let down = Node {
-        start: (0, 0).into(),
-        direction: (0, 1).into(),
-    };
-    queue.push((0, right));
-    queue.push((0, down));
-    distances.insert(right, 0);
-    distances.insert(down, 0);
-
-    while let Some((_, current)) = queue.pop() {
-        if !visited.insert(current) {
-            continue;
-        };
-
+    let finish = map.bounds() + Point2D::new(-1, -1);
+    let starts = [(1, 0), (0, 1)].map(|direction| Node {
+        start: Point2D::zero(),
+        direction: direction.into(),
+    });
+    let successors = |current: &Node| {
+        let mut result = vec![];
         for direction in next_directions(&current.direction) {
             let (_, bearing) = direction.manhattan_normalize();
-            let next_pos = current.start + bearing;
-            let move_cost = map.get(&next_pos);
-            if move_cost.is_none() {
-                continue; // out of bounds
+            let start = current.start + bearing;
+            if let Some(&cost) = map.get(start) {
+                result.push((Node { start, direction }, cost as i32));
             }
-            let cost = *move_cost.unwrap() as i32;
-
-            let next = Node {
-                start: next_pos,
-                direction,
-            };
-            let distance = distances
-                .get(&next)
-                .or(Some(&i32::MAX))
-                .unwrap()
-                .clone()
-                .min(distances[&current] + cost);
-
-            distances.insert(next, distance);
-            queue.push((-distance, next)); // min queue
         }
-    }
-
-    distances
-        .iter()
-        .filter(|(k, _)| k.start == finish && is_valid_solution(k))
-        .map(|(_, v)| *v)
-        .min()
-        .expect("no result")
-}
-
-fn part1(input: &str) -> i32 {
-    solve(
-        input,
-        |direction| {
-            let (steps, bearing) = direction.manhattan_normalize();
-            let mut result = vec![bearing.clockwise(), bearing.counter_clockwise()];
-            if steps < 3 {
-                result.insert(0, direction + bearing);
-            }
-            return result;
-        },
-        |_| true,
+        return result;
+    };
+    // every block costs at least 1, so the manhattan distance never overestimates
+    let heuristic = |node: &Node| (finish.x - node.start.x) + (finish.y - node.start.y);
+
+    search::astar(
+        starts,
+        successors,
+        |node| node.start == finish && is_valid_solution(node),
+        heuristic,
     )
+    .expect("no result")
+    .cost
 }
 
-fn part2(input: &str) -> i32 {
-    solve(
-        input,
-        |direction| {
-            let (steps, bearing) = direction.manhattan_normalize();
-            let mut result = vec![];
-            if steps < 10 {
-                result.push(bearing + direction);
-            }
-            if steps >= 4 {
-                result.push(bearing.clockwise());
-                result.push(bearing.counter_clockwise());
-            }
-            return result;
-        },
-        |node| node.direction.manhattan_len() >= 4,
-    )
+impl Solution for Day17 {
+    type Parsed = Map;
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        parse_map(input)
+    }
+
+    fn part1(map: &Self::Parsed) -> Answer {
+        solve(
+            map,
+            |direction| {
+                let (steps, bearing) = direction.manhattan_normalize();
+                let mut result = vec![bearing.clockwise(), bearing.counter_clockwise()];
+                if steps < 3 {
+                    result.insert(0, direction + bearing);
+                }
+                return result;
+            },
+            |_| true,
+        )
+        .into()
+    }
+
+    fn part2(map: &Self::Parsed) -> Answer {
+        solve(
+            map,
+            |direction| {
+                let (steps, bearing) = direction.manhattan_normalize();
+                let mut result = vec![];
+                if steps < 10 {
+                    result.push(bearing + direction);
+                }
+                if steps >= 4 {
+                    result.push(bearing.clockwise());
+                    result.push(bearing.counter_clockwise());
+                }
+                return result;
+            },
+            |node| node.direction.manhattan_len() >= 4,
+        )
+        .into()
+    }
 }
 
 #[cfg(test)]
@@ -142,27 +133,41 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        let result = part1(EXAMPLE);
+        let result = Day17::part1(&Day17::parse(EXAMPLE).unwrap());
         assert_eq!(result, 102)
     }
 
     #[test]
     fn part1_result() {
         let input = utils::resource("src/day17.txt");
-        let result = part1(&input);
+        let result = Day17::part1(&Day17::parse(&input).unwrap());
         assert_eq!(result, 758);
     }
 
     #[test]
     fn part2_example() {
-        let result = part2(EXAMPLE);
+        let result = Day17::part2(&Day17::parse(EXAMPLE).unwrap());
         assert_eq!(result, 94)
     }
 
     #[test]
     fn part2_result() {
         let input = utils::resource("src/day17.txt");
-        let result = part2(&input);
+        let result = Day17::part2(&Day17::parse(&input).unwrap());
         assert_eq!(result, 892);
     }
+
+    #[test]
+    fn parse_error() {
+        let result = Day17::parse("2413432311323\n32154535356_3");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(2, 12, ErrorKind::ExpectedDigit('_')))
+        );
+        let result = Day17::parse("\n");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(1, 1, ErrorKind::EmptyGrid(EmptyGrid)))
+        );
+    }
 }
@@ -1,66 +1,121 @@
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
+use crate::utils::parse::{self, Span};
 use crate::utils::*;
+use thiserror::Error;
 type Int = i64;
 
-fn area(edges: Vec<Point2D>) -> Int {
-    let row_area: Int = edges
-        .iter()
-        .zip(edges.iter().cycle().skip(1))
-        .map(|(a, b)| ((a * -1) + b).manhattan_len() as Int)
-        .sum::<Int>();
-    let shoelace_area: Int = edges
-        .iter()
-        .zip(edges.iter().cycle().skip(1))
-        .map(|(a, b)| a.x as Int * b.y as Int - a.y as Int * b.x as Int)
-        .sum::<Int>()
-        .abs()
-        / 2;
-    (row_area / 2 + 1) + shoelace_area
+pub struct Day18;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("expected '<direction> <steps> (#<color>)'")]
+    InvalidStep,
+    #[error("invalid direction '{0}', expected 'U', 'R', 'D' or 'L'")]
+    InvalidDirection(String),
+    #[error("invalid number of steps '{0}'")]
+    InvalidSteps(String),
+    #[error("invalid color '{0}', expected '(#<5 hex digits><0 to 3>)'")]
+    InvalidColor(String),
 }
 
-fn part1(input: &str) -> Int {
-    let mut edges = vec![];
-    let mut current = Point2D::zero();
-    edges.push(current);
-    for line in input.trim().split('\n') {
-        let items: Vec<&str> = line.split(' ').collect();
-        let direction = match items[0] {
+impl Hint for ErrorKind {
+    fn hint(&self) -> Option<String> {
+        Some("steps look like 'R 6 (#70c710)'".to_string())
+    }
+}
+
+/// the color encodes the steps as 5 hex digits and the direction as a single 0..=3 digit
+fn is_valid_color(color: &str) -> bool {
+    return color.len() == 9
+        && color.starts_with("(#")
+        && color.ends_with(')')
+        && color[2..7].chars().all(|c| c.is_ascii_hexdigit())
+        && ('0'..='3').any(|c| color[7..8].starts_with(c));
+}
+
+/// a single line of the dig plan, the color holds the real instruction for part 2
+pub struct DigStep {
+    direction: Direction,
+    steps: Int,
+    color: String,
+}
+
+fn parse_plan(input: &str) -> Result<Vec<DigStep>, ParseError<ErrorKind>> {
+    let mut plan = vec![];
+    for line in parse::lines(input) {
+        let items: Vec<Span> = line.split(" ").collect();
+        if items.len() != 3 {
+            return Err(ParseError::new(line.line_no, 1, ErrorKind::InvalidStep));
+        }
+        let direction = match items[0].text {
             "U" => Direction::Up,
             "R" => Direction::Right,
             "D" => Direction::Down,
             "L" => Direction::Left,
-            x => panic!("unexpected direction: {}", x),
+            x => return Err(items[0].error(ErrorKind::InvalidDirection(x.to_string()))),
         };
-        let steps = items[1].parse::<i32>().expect("NaN");
-        let next = Point2D::zero().translate(direction) * steps as i32;
-        current = current + next;
-        edges.push(current);
+        let steps = items[1].parse(|x| ErrorKind::InvalidSteps(x.to_string()))?;
+        if !is_valid_color(items[2].text) {
+            return Err(items[2].error(ErrorKind::InvalidColor(items[2].text.to_string())));
+        }
+        let color = items[2].text.to_string();
+        plan.push(DigStep {
+            direction,
+            steps,
+            color,
+        });
     }
+    return Ok(plan);
+}
 
-    area(edges)
+fn decode_color(color: &str) -> (Direction, Int) {
+    let steps_hex = &color[2..7];
+    let steps = Int::from_str_radix(steps_hex, 16).expect("invalid number");
+
+    let direction = match &color[7..8] {
+        "0" => Direction::Right,
+        "1" => Direction::Down,
+        "2" => Direction::Left,
+        "3" => Direction::Up,
+        x => panic!("unexpected direction: {}", x),
+    };
+    return (direction, steps);
 }
 
-fn part2(input: &str) -> Int {
+fn dig<I>(plan: I) -> Int
+where
+    I: Iterator<Item = (Direction, Int)>,
+{
     let mut edges = vec![];
     let mut current = Point2D::zero();
     edges.push(current);
-    for line in input.trim().split('\n') {
-        let items: Vec<&str> = line.split(' ').collect();
-        let steps_hex = &items[2][2..7];
-        let steps = i32::from_str_radix(steps_hex, 16).expect("invalid number");
-
-        let direction = match &items[2][7..8] {
-            "0" => Direction::Right,
-            "1" => Direction::Down,
-            "2" => Direction::Left,
-            "3" => Direction::Up,
-            x => panic!("unexpected direction: {}", x),
-        };
+    for (direction, steps) in plan {
         let next = Point2D::zero().translate(direction) * steps;
         current = current + next;
         edges.push(current);
     }
 
-    area(edges)
+    // the trench is the boundary of the lagoon and everything inside it is dug out too
+    polygon::boundary_points(&edges) + polygon::interior_points(&edges)
+}
+
+impl Solution for Day18 {
+    type Parsed = Vec<DigStep>;
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        parse_plan(input)
+    }
+
+    fn part1(plan: &Self::Parsed) -> Answer {
+        dig(plan.iter().map(|step| (step.direction, step.steps))).into()
+    }
+
+    fn part2(plan: &Self::Parsed) -> Answer {
+        dig(plan.iter().map(|step| decode_color(&step.color))).into()
+    }
 }
 
 #[cfg(test)]
@@ -86,27 +141,54 @@ U 2 (#7a21e3)";
 
     #[test]
     fn part1_example() {
-        let result = part1(EXAMPLE);
+        let result = Day18::part1(&Day18::parse(EXAMPLE).unwrap());
         assert_eq!(result, 62)
     }
 
     #[test]
     fn part1_result() {
         let input = utils::resource("src/day18.txt");
-        let result = part1(&input);
+        let result = Day18::part1(&Day18::parse(&input).unwrap());
         assert_eq!(result, 48652);
     }
 
     #[test]
     fn part2_example() {
-        let result = part2(EXAMPLE);
-        assert_eq!(result, 952408144115)
+        let result = Day18::part2(&Day18::parse(EXAMPLE).unwrap());
+        assert_eq!(result, 952408144115_i64)
     }
 
     #[test]
     fn part2_result() {
         let input = utils::resource("src/day18.txt");
-        let result = part2(&input);
-        assert_eq!(result, 45757884535661);
+        let result = Day18::part2(&Day18::parse(&input).unwrap());
+        assert_eq!(result, 45757884535661_i64);
+    }
+
+    #[test]
+    fn parse_error() {
+        let result = Day18::parse("R 6 (#70c710)\nX 5 (#0dc571)");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(
+                2,
+                1,
+                ErrorKind::InvalidDirection("X".to_string())
+            ))
+        );
+        let result = Day18::parse("R 6 (#70c710)\nD 5 (#0dc574)");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(
+                2,
+                5,
+                ErrorKind::InvalidColor("(#0dc574)".to_string())
+            ))
+        );
+        let result = Day18::parse("R 6");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(1, 1, ErrorKind::InvalidStep))
+        );
     }
 }
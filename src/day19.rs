@@ -1,15 +1,60 @@
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
+use crate::utils::parse::{self, Span};
 use crate::utils::*;
 use core::panic;
 use std::collections::HashMap;
+use thiserror::Error;
 type Int = u128;
 type Part = [Int; 4];
 const PART_LAYOUT: &str = "xmas";
 
-enum Rule {
+pub struct Day19;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("expected '<name>{{<rules>}}'")]
+    MissingBraces,
+    #[error("unknown category {0:?}, expected one of 'xmas'")]
+    UnknownCategory(char),
+    #[error("unknown operator {0:?}, expected '<' or '>'")]
+    UnknownOperator(char),
+    #[error("invalid value '{0}'")]
+    InvalidValue(String),
+    #[error("expected '{{x=<value>,m=<value>,a=<value>,s=<value>}}'")]
+    InvalidRating,
+    #[error("expected the part ratings after an empty line")]
+    MissingRatings,
+    #[error("unknown workflow '{0}'")]
+    UnknownWorkflow(String),
+    #[error("the last rule of a workflow can't have a condition")]
+    MissingFallback,
+}
+
+impl Hint for ErrorKind {
+    fn hint(&self) -> Option<String> {
+        match self {
+            ErrorKind::InvalidRating => {
+                Some("ratings look like '{x=787,m=2655,a=1222,s=2876}'".to_string())
+            }
+            ErrorKind::MissingRatings | ErrorKind::InvalidValue(_) => None,
+            ErrorKind::UnknownWorkflow(_) => {
+                Some("rules lead to a workflow, 'A' or 'R'".to_string())
+            }
+            ErrorKind::MissingFallback => {
+                Some("the last rule is where the parts no other rule matches go".to_string())
+            }
+            _ => Some("workflows look like 'px{a<2006:qkq,m>2090:A,rfg}'".to_string()),
+        }
+    }
+}
+
+pub enum Rule {
     Goto(String),
     If(Condition, String),
 }
-struct Condition {
+pub struct Condition {
     part_category: usize,
     operator: char,
     value: Int,
@@ -53,133 +98,159 @@ fn accept_or_reject(system: &System, part: &Part) -> bool {
     }
 }
 
-fn parse_system(input: &str) -> System {
+fn parse_rule(rule: Span) -> Result<Rule, ParseError<ErrorKind>> {
+    let (condition, then) = match rule.split_once(":") {
+        Some(x) => x,
+        None => return Ok(Rule::Goto(rule.text.to_string())),
+    };
+    let mut chars = condition.text.chars();
+    let part_char = chars.next().unwrap_or(':');
+    let part_category = PART_LAYOUT
+        .find(part_char)
+        .ok_or(condition.error(ErrorKind::UnknownCategory(part_char)))?;
+    let operator = chars.next().unwrap_or(':');
+    if operator != '<' && operator != '>' {
+        let kind = ErrorKind::UnknownOperator(operator);
+        return Err(condition.skip(1).error(kind));
+    }
+    let value = condition
+        .skip(2)
+        .parse(|x| ErrorKind::InvalidValue(x.to_string()))?;
+
+    Ok(Rule::If(
+        Condition {
+            part_category,
+            operator,
+            value,
+        },
+        then.text.to_string(),
+    ))
+}
+
+fn parse_system(lines: &[Span]) -> Result<System, ParseError<ErrorKind>> {
     let mut system = System::new();
-    for line in input.split('\n') {
-        let rules_start = line.find('{').unwrap();
-        let line_rules = line[rules_start + 1..]
-            .trim_end_matches('}')
-            .split(',')
-            .map(|rule_str| {
-                if let Some(then_pos) = rule_str.find(':') {
-                    let then_str = rule_str[then_pos + 1..].to_string();
-                    let part_char = &rule_str[0..1];
-                    let part_category = PART_LAYOUT.find(part_char).unwrap();
-                    let operator = rule_str[1..2].chars().nth(0).unwrap();
-                    let value = rule_str[2..then_pos].parse::<Int>().unwrap();
-
-                    Rule::If(
-                        Condition {
-                            part_category,
-                            operator,
-                            value,
-                        },
-                        then_str,
-                    )
-                } else {
-                    Rule::Goto(rule_str.to_string())
-                }
-            })
-            .collect::<Vec<Rule>>();
-        let name = line[0..rules_start].to_string();
-        system.insert(name, line_rules);
+    let mut targets = Vec::<Span>::new();
+    for line in lines {
+        let (name, rules) = line.block('{', '}').ok_or(ParseError::new(
+            line.line_no,
+            1,
+            ErrorKind::MissingBraces,
+        ))?;
+        let line_rules = rules
+            .split(",")
+            .map(parse_rule)
+            .collect::<Result<Vec<Rule>, _>>()?;
+        // every part has to leave the workflow somewhere
+        if let Some(Rule::If(..)) = line_rules.last() {
+            let last = rules.split(",").last().unwrap_or(rules);
+            return Err(last.error(ErrorKind::MissingFallback));
+        }
+        for rule in rules.split(",") {
+            targets.push(rule.split_once(":").map_or(rule, |(_, then)| then));
+        }
+        system.insert(name.text.to_string(), line_rules);
     }
-    return system;
+
+    // all the rules need to lead somewhere
+    let is_known = |name: &str| name == "A" || name == "R" || system.contains_key(name);
+    if let Some(target) = targets.iter().find(|t| !is_known(t.text)) {
+        return Err(target.error(ErrorKind::UnknownWorkflow(target.text.to_string())));
+    }
+    if !system.contains_key("in") {
+        return Err(ParseError::new(
+            1,
+            1,
+            ErrorKind::UnknownWorkflow("in".to_string()),
+        ));
+    }
+    return Ok(system);
 }
 
-fn part1(input: &str) -> Int {
-    let input_parts = input.trim().split("\n\n").collect::<Vec<&str>>();
-
-    let system = parse_system(input_parts[0]);
-
-    let mut part_ratings = Vec::<Part>::new();
-    for line in input_parts[1].split('\n') {
-        let ints = line
-            .split(',')
-            .map(|p| {
-                p[p.find('=').unwrap() + 1..]
-                    .trim_end_matches('}')
-                    .parse::<Int>()
-                    .unwrap()
-            })
-            .collect::<Vec<Int>>();
-        part_ratings.push(ints.try_into().unwrap());
-    }
-    let part_ratings = part_ratings;
-    part_ratings
-        .into_iter()
-        .filter(|p| accept_or_reject(&system, p))
-        .map(|p| p.into_iter().sum::<Int>())
-        .sum()
+fn parse_rating(line: &Span) -> Result<Part, ParseError<ErrorKind>> {
+    let invalid_rating = || ParseError::new(line.line_no, 1, ErrorKind::InvalidRating);
+    let ratings = match line.block('{', '}') {
+        Some((label, ratings)) if label.is_empty() => ratings,
+        _ => return Err(invalid_rating()),
+    };
+    let ratings: Vec<Span> = ratings.split(",").collect();
+    if ratings.len() != PART_LAYOUT.len() {
+        return Err(invalid_rating());
+    }
+
+    let mut part: Part = [0; 4];
+    for (i, (rating, category)) in ratings.iter().zip(PART_LAYOUT.chars()).enumerate() {
+        part[i] = match rating.key_value("=") {
+            Some((key, value)) if key.text == category.to_string() => {
+                value.parse(|x| ErrorKind::InvalidValue(x.to_string()))?
+            }
+            _ => return Err(invalid_rating()),
+        };
+    }
+    return Ok(part);
 }
 
-type Wave = [Point2D; 4];
+fn parse_input(input: &str) -> Result<(System, Vec<Part>), ParseError<ErrorKind>> {
+    let sections = parse::sections(input);
+    let (workflows, ratings) = match sections.split_first() {
+        Some((workflows, ratings)) if !ratings.is_empty() => (workflows, ratings.concat()),
+        Some((workflows, _)) => {
+            let last = workflows[workflows.len() - 1];
+            return Err(last.error_after(ErrorKind::MissingRatings));
+        }
+        None => return Err(ParseError::new(1, 1, ErrorKind::MissingRatings)),
+    };
+
+    let system = parse_system(workflows)?;
+    let part_ratings = ratings
+        .iter()
+        .map(parse_rating)
+        .collect::<Result<Vec<Part>, _>>()?;
+    return Ok((system, part_ratings));
+}
 
-fn part2(input: &str) -> Int {
-    let input_parts = input.trim().split("\n\n").collect::<Vec<&str>>();
-    let system = parse_system(input_parts[0]);
+/// the ratings of each category that lead to a workflow
+type Wave = [RangeSet<Int>; 4];
 
+fn accepted_combinations(system: &System) -> Int {
     let mut work = Vec::<(String, Wave)>::new();
+    let all_ratings = RangeSet::from(1..4001);
     work.push((
         "in".to_string(),
-        [
-            (1, 4000).into(),
-            (1, 4000).into(),
-            (1, 4000).into(),
-            (1, 4000).into(),
-        ],
+        std::array::from_fn(|_| all_ratings.clone()),
     ));
     let mut accepted = vec![];
 
-    while let Some((workflow_name, mut wave_reamining)) = work.pop() {
+    while let Some((workflow_name, mut wave_remaining)) = work.pop() {
         if workflow_name == "A" {
-            accepted.push(wave_reamining);
+            accepted.push(wave_remaining);
         } else if workflow_name == "R" {
         } else if let Some(workflow) = system.get(&workflow_name) {
             for rule in workflow {
                 match rule {
                     Rule::Goto(next) => {
-                        work.push((next.clone(), wave_reamining));
+                        work.push((next.clone(), wave_remaining));
+                        break;
                     }
                     Rule::If(condition, next) => {
-                        let range = wave_reamining[condition.part_category];
-                        let (start, end) = (range.x, range.y);
-                        let val = condition.value as i32;
-
-                        let mut matching: Option<Point2D> = None;
-                        let mut remaining: Option<Point2D> = None;
-                        match condition.operator {
+                        let ratings = &wave_remaining[condition.part_category];
+                        let (matching, remaining) = match condition.operator {
                             '>' => {
-                                if start > val {
-                                    matching = Some(range);
-                                } else if end < val {
-                                    remaining = Some(range);
-                                } else {
-                                    matching = Some(Point2D::new(val + 1, end));
-                                    remaining = Some(Point2D::new(start, val));
-                                }
-                            }
-                            '<' => {
-                                if end < val {
-                                    matching = Some(range);
-                                } else if start > val {
-                                    remaining = Some(range);
-                                } else {
-                                    matching = Some(Point2D::new(start, val - 1));
-                                    remaining = Some(Point2D::new(val, end));
-                                }
+                                let (below, above) = ratings.split_at(condition.value + 1);
+                                (above, below)
                             }
+                            '<' => ratings.split_at(condition.value),
                             x => panic!("unexpected operator {}", x),
                         };
 
-                        if matching.is_some() {
-                            wave_reamining[condition.part_category] = matching.unwrap();
-                            work.push((next.clone(), wave_reamining));
+                        if !matching.is_empty() {
+                            let mut wave_matching = wave_remaining.clone();
+                            wave_matching[condition.part_category] = matching;
+                            work.push((next.clone(), wave_matching));
                         }
-
-                        if remaining.is_some() {
-                            wave_reamining[condition.part_category] = remaining.unwrap();
+                        if remaining.is_empty() {
+                            break;
                         }
+                        wave_remaining[condition.part_category] = remaining;
                     }
                 }
             }
@@ -190,10 +261,32 @@ fn part2(input: &str) -> Int {
 
     return accepted
         .iter()
-        .map(|wave| wave.iter().fold(1, |agg, r| agg * (r.y - r.x + 1) as Int))
+        .map(|wave| wave.iter().map(RangeSet::len).product::<Int>())
         .sum::<Int>();
 }
 
+impl Solution for Day19 {
+    type Parsed = (System, Vec<Part>);
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        parse_input(input)
+    }
+
+    fn part1((system, part_ratings): &Self::Parsed) -> Answer {
+        part_ratings
+            .iter()
+            .filter(|p| accept_or_reject(system, p))
+            .map(|p| p.iter().sum::<Int>())
+            .sum::<Int>()
+            .into()
+    }
+
+    fn part2((system, _): &Self::Parsed) -> Answer {
+        accepted_combinations(system).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -221,27 +314,62 @@ hdj{m>838:A,pv}
 
     #[test]
     fn part1_example() {
-        let result = part1(EXAMPLE);
+        let result = Day19::part1(&Day19::parse(EXAMPLE).unwrap());
         assert_eq!(result, 19114)
     }
 
     #[test]
     fn part1_result() {
         let input = utils::resource("src/day19.txt");
-        let result = part1(&input);
+        let result = Day19::part1(&Day19::parse(&input).unwrap());
         assert_eq!(result, 373302);
     }
 
     #[test]
     fn part2_example() {
-        let result = part2(EXAMPLE);
-        assert_eq!(result, 167409079868000)
+        let result = Day19::part2(&Day19::parse(EXAMPLE).unwrap());
+        assert_eq!(result, 167409079868000_i64)
     }
 
     #[test]
     fn part2_result() {
         let input = utils::resource("src/day19.txt");
-        let result = part2(&input);
-        assert_eq!(result, 130262715574114);
+        let result = Day19::part2(&Day19::parse(&input).unwrap());
+        assert_eq!(result, 130262715574114_i64);
+    }
+
+    #[test]
+    fn parse_error() {
+        let result = Day19::parse(
+            "in{s<1351:px,R}\npx{a<2006:A,y>2090:R,A}\n\n{x=787,m=2655,a=1222,s=2876}",
+        );
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(2, 13, ErrorKind::UnknownCategory('y')))
+        );
+        let result = Day19::parse("in{s<1351:px,R}\n\n{x=787,m=2655,a=1222,s=2876}");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(
+                1,
+                11,
+                ErrorKind::UnknownWorkflow("px".to_string())
+            ))
+        );
+        let result = Day19::parse("in{s<1351:A,R}\n\n{x=787,m=2655,a=1222}");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(3, 1, ErrorKind::InvalidRating))
+        );
+        let result = Day19::parse("in{s<1351:A,R}");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(1, 15, ErrorKind::MissingRatings))
+        );
+        let result = Day19::parse("in{x<5:A}\n\n{x=7,m=1,a=1,s=1}");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(1, 4, ErrorKind::MissingFallback))
+        );
     }
 }
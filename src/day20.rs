@@ -1,27 +1,63 @@
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::{Params, Solution};
+use crate::utils::{cycle, parse};
 use std::collections::HashMap;
+use thiserror::Error;
 type Int = usize;
-enum Module {
+const PART1_ITERATIONS: usize = 1000;
+
+pub struct Day20;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("expected '<module> -> <destinations>'")]
+    MissingArrow,
+    #[error("missing module name")]
+    MissingName,
+    #[error("unknown module type {0:?}, expected 'broadcaster', '%' or '&'")]
+    UnknownModuleType(char),
+}
+
+impl Hint for ErrorKind {
+    fn hint(&self) -> Option<String> {
+        Some("modules look like 'broadcaster -> a, b', '%a -> b' or '&inv -> a'".to_string())
+    }
+}
+
+pub enum Module {
     Broadcaster,
     FlipFlop,
     Conjunction,
 }
 
-type System = HashMap<String, (Module, Vec<String>)>;
-fn parse_system(input: &str) -> System {
+pub type System = HashMap<String, (Module, Vec<String>)>;
+fn parse_system(input: &str) -> Result<System, ParseError<ErrorKind>> {
     let mut result = System::new();
-    for line in input.trim().split('\n') {
-        let split = line.split(" -> ").collect::<Vec<&str>>();
-        let into = split[1].split(", ").map(|x| x.to_string()).collect();
-        let from = split[0].to_string();
-        let (name, module) = match from.as_bytes()[0] {
-            b'b' => (from, (Module::Broadcaster, into)),
-            b'%' => (from[1..].to_string(), (Module::FlipFlop, into)),
-            b'&' => (from[1..].to_string(), (Module::Conjunction, into)),
-            x => panic!("unknown module type '{}'", x as char),
+    for line in parse::lines(input) {
+        let (from, into) = line
+            .key_value("->")
+            .ok_or(line.error_after(ErrorKind::MissingArrow))?;
+        let into = into
+            .split(",")
+            .map(|target| match target.trim() {
+                x if x.is_empty() => Err(x.error(ErrorKind::MissingName)),
+                x => Ok(x.text.to_string()),
+            })
+            .collect::<Result<Vec<String>, _>>()?;
+        let (name, module) = match from.text.chars().next() {
+            _ if from.text == "broadcaster" => (from, (Module::Broadcaster, into)),
+            Some('%') => (from.skip(1), (Module::FlipFlop, into)),
+            Some('&') => (from.skip(1), (Module::Conjunction, into)),
+            Some(x) => return Err(from.error(ErrorKind::UnknownModuleType(x))),
+            None => return Err(from.error(ErrorKind::MissingName)),
         };
-        result.insert(name, module);
+        if name.is_empty() {
+            return Err(name.error(ErrorKind::MissingName));
+        }
+        result.insert(name.text.to_string(), module);
     }
-    return result;
+    return Ok(result);
 }
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Signal {
@@ -85,12 +121,12 @@ fn push_button<T>(
     }
 }
 
-fn init_memory(
-    system: &System,
-) -> (
+type Memory = (
     HashMap<String, bool>,
     HashMap<String, HashMap<String, Signal>>,
-) {
+);
+
+fn init_memory(system: &System) -> Memory {
     // init memory
     let mut states_flip_flop = HashMap::new();
     let mut states_conjunction = HashMap::new();
@@ -113,58 +149,98 @@ fn init_memory(
     (states_flip_flop, states_conjunction)
 }
 
-fn part1(input: &str, iterations: i32) -> Int {
-    let system = &parse_system(input);
+/// the memory as a list of bits, ordered by module (and input) name
+fn fingerprint((states_flip_flop, states_conjunction): &Memory) -> Vec<bool> {
+    let mut flip_flops: Vec<_> = states_flip_flop.iter().collect();
+    flip_flops.sort();
+    let mut conjunctions: Vec<_> = states_conjunction
+        .iter()
+        .flat_map(|(name, inputs)| inputs.iter().map(move |(input, &s)| (name, input, s)))
+        .collect();
+    conjunctions.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+
+    let flip_flops = flip_flops.into_iter().map(|(_, &on)| on);
+    let conjunctions = conjunctions.into_iter().map(|(_, _, s)| s == Signal::High);
+    return flip_flops.chain(conjunctions).collect();
+}
+
+/// pushes the button once, returns the memory after it and the high and low pulses sent
+fn press(system: &System, memory: &Memory) -> (Memory, (Int, Int)) {
+    let (mut states_flip_flop, mut states_conjunction) = memory.clone();
     let mut highs = 0;
     let mut lows = 0;
-    let (mut states_flip_flop, mut states_conjunction) = init_memory(system);
-    // push button
-    for _ in 0..iterations {
-        push_button(
-            &system,
-            &mut states_flip_flop,
-            &mut states_conjunction,
-            &mut |_, signal, _| match signal {
-                Signal::High => {
-                    highs += 1;
-                }
-                Signal::Low => {
-                    lows += 1;
-                } // println!("{} -{:#?}- -> {}", source, signal, receiver);
-            },
-        );
+    push_button(
+        system,
+        &mut states_flip_flop,
+        &mut states_conjunction,
+        &mut |_, signal, _| match signal {
+            Signal::High => {
+                highs += 1;
+            }
+            Signal::Low => {
+                lows += 1;
+            } // println!("{} -{:#?}- -> {}", source, signal, receiver);
+        },
+    );
+    ((states_flip_flop, states_conjunction), (highs, lows))
+}
+
+fn pulse_product(system: &System, iterations: usize) -> Int {
+    if iterations == 0 {
+        return 0;
+    }
+    // the pulses of a press only depend on the memory before it, once that repeats so do the
+    // pulses, which skips most of the presses for large iteration counts
+    let history = cycle::simulate_by(
+        init_memory(system),
+        |memory| press(system, memory).0,
+        fingerprint,
+        iterations - 1,
+    );
+
+    let mut highs = 0;
+    let mut lows = 0;
+    for (memory, visits) in history.states.iter().zip(history.visits(iterations - 1)) {
+        let (h, l) = press(system, memory).1;
+        highs += h * visits;
+        lows += l * visits;
     }
     highs * lows
 }
 
-fn part2(input: &str) -> Int {
-    let system = &parse_system(input);
+/// the presses until `rx` gets a low pulse, or why the system isn't shaped like the puzzle's
+fn presses_until_rx(system: &System) -> Result<Int, String> {
     let (ref mut states_flip_flop, ref mut states_conjunction) = init_memory(system);
 
     let rx_source = system
         .iter()
-        .filter_map(|(key, (_, targets))| {
-            if targets.len() == 1 && targets[0] == "rx" {
-                Some(key.clone())
-            } else {
-                None
-            }
-        })
+        .filter(|(_, (_, targets))| targets.iter().any(|t| t == "rx"))
+        .map(|(key, _)| key.clone())
         .collect::<Vec<String>>();
 
     // find out how many times we need to press the button to get a high signal into each
     // of inputs flowing into th the rx_proxy conjunction that sends the low signal to rx
-    let rx_proxy_name = &rx_source[0].to_string();
+    let rx_proxy_name = match rx_source.as_slice() {
+        [] => return Err("no module sends pulses to 'rx'".to_string()),
+        [name] => name,
+        _ => return Err("more than one module sends pulses to 'rx'".to_string()),
+    };
     let mut rx_proxy_inputs = HashMap::<String, Int>::new();
-    for (key, _) in &states_conjunction[rx_proxy_name] {
+    let rx_proxy_memory = states_conjunction.get(rx_proxy_name).ok_or(format!(
+        "'{}' sends pulses to 'rx', but isn't a conjunction",
+        rx_proxy_name
+    ))?;
+    for key in rx_proxy_memory.keys() {
         rx_proxy_inputs.insert(key.clone(), 0);
     }
 
     let mut cnt = 0;
     loop {
         if rx_proxy_inputs.values().all(|&x| x > 0) {
-            // luckily those are all primes...
-            return rx_proxy_inputs.values().product();
+            // each input is high every so many presses, they are all high at the lcm of those
+            return Ok(rx_proxy_inputs
+                .values()
+                .fold(1, |agg, &next| num::integer::lcm(agg, next)));
         }
         cnt += 1;
         push_button(
@@ -172,7 +248,8 @@ fn part2(input: &str) -> Int {
             states_flip_flop,
             states_conjunction,
             &mut |source, signal, _| {
-                if rx_proxy_inputs.contains_key(source) && signal == Signal::High {
+                // only the first high counts, later ones are multiples of it
+                if signal == Signal::High && rx_proxy_inputs.get(source) == Some(&0) {
                     rx_proxy_inputs.insert(source.to_string(), cnt);
                 }
             },
@@ -180,51 +257,254 @@ fn part2(input: &str) -> Int {
     }
 }
 
+impl Solution for Day20 {
+    type Parsed = System;
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        parse_system(input)
+    }
+
+    fn part1(system: &Self::Parsed) -> Answer {
+        pulse_product(system, PART1_ITERATIONS).into()
+    }
+
+    fn part1_with(system: &Self::Parsed, params: &Params) -> Answer {
+        pulse_product(system, params.get("iterations", PART1_ITERATIONS)).into()
+    }
+
+    fn part2(system: &Self::Parsed) -> Answer {
+        match presses_until_rx(system) {
+            Ok(presses) => presses.into(),
+            Err(reason) => Answer::Unsolvable(reason),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::utils;
+    use crate::utils::differential::{self, Rng};
 
     #[test]
     fn part1_example1() {
-        let result = part1(
-            r#"
+        let result = pulse_product(
+            &Day20::parse(
+                r#"
 broadcaster -> a, b, c
 %a -> b
 %b -> c
 %c -> inv
 &inv -> a
 "#,
+            )
+            .unwrap(),
             1,
         );
         assert_eq!(result, 4 * 8)
     }
     #[test]
     fn part1_example2() {
-        let result = part1(
-            r#"
+        let result = Day20::part1(
+            &Day20::parse(
+                r#"
 broadcaster -> a
 %a -> inv, con
 &inv -> b
 %b -> con
 &con -> output
 "#,
-            1000,
+            )
+            .unwrap(),
         );
         assert_eq!(result, 11687500)
     }
 
+    #[test]
+    fn part1_repeating() {
+        let system = Day20::parse(
+            r#"
+broadcaster -> a
+%a -> inv, con
+&inv -> b
+%b -> con
+&con -> output
+"#,
+        )
+        .unwrap();
+        // the memory repeats every 4 presses, 1000 presses send 4250 low and 2750 high pulses
+        let result = pulse_product(&system, 1_000_000_000);
+        assert_eq!(result, 4_250_000_000 * 2_750_000_000);
+    }
+
     #[test]
     fn part1_result() {
         let input = utils::resource("src/day20.txt");
-        let result = part1(&input, 1000);
+        let result = Day20::part1(&Day20::parse(&input).unwrap());
         assert_eq!(result, 814934624);
     }
 
     #[test]
     fn part2_result() {
         let input = utils::resource("src/day20.txt");
-        let result = part2(&input);
-        assert_eq!(result, 228282646835717);
+        let result = Day20::part2(&Day20::parse(&input).unwrap());
+        assert_eq!(result, 228282646835717_i64);
+    }
+
+    #[test]
+    fn part2_unsolvable() {
+        let input = utils::resource("src/day20.example.txt");
+        let result = Day20::part2(&Day20::parse(&input).unwrap());
+        assert_eq!(
+            result,
+            Answer::Unsolvable("no module sends pulses to 'rx'".to_string())
+        );
+        let result = Day20::part2(&Day20::parse("broadcaster -> a\n%a -> rx").unwrap());
+        assert_eq!(
+            result,
+            Answer::Unsolvable("'a' sends pulses to 'rx', but isn't a conjunction".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_error() {
+        let result = Day20::parse("broadcaster -> a\n%a -> inv, con\n&inv b");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(3, 7, ErrorKind::MissingArrow))
+        );
+        let result = Day20::parse("broadcaster -> a\n$a -> b");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(2, 1, ErrorKind::UnknownModuleType('$')))
+        );
+        let result = Day20::parse("broadcaster -> a\n% -> b");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(2, 2, ErrorKind::MissingName))
+        );
+    }
+
+    /// a few modules wired at random, pulses only go to later modules. loops can send pulses
+    /// forever, the ones of the puzzle are covered by the counters below.
+    fn generate_system(rng: &mut Rng) -> (String, usize) {
+        let names = ["a", "b", "c", "d", "e", "f"];
+        let count = rng.range(2..7) as usize;
+        let mut broadcast: Vec<&str> = (0..rng.range(1..4))
+            .map(|_| names[rng.below(count)])
+            .collect();
+        broadcast.sort();
+        broadcast.dedup();
+        let mut lines = vec![format!("broadcaster -> {}", broadcast.join(", "))];
+        for (i, name) in names.iter().enumerate().take(count) {
+            let mut targets: Vec<&str> = (0..rng.range(1..4))
+                .filter_map(|_| match rng.below(count + 1) {
+                    j if j == count => Some("output"),
+                    j if j > i => Some(names[j]),
+                    _ => None,
+                })
+                .collect();
+            targets.sort();
+            targets.dedup();
+            if targets.is_empty() {
+                targets.push("output");
+            }
+            let kind = if rng.chance(40) { '&' } else { '%' };
+            lines.push(format!("{}{} -> {}", kind, name, targets.join(", ")));
+        }
+        return (lines.join("\n"), rng.range(1..300) as usize);
+    }
+
+    fn brute_force_pulses(system: &System, iterations: usize) -> Int {
+        let mut memory = init_memory(system);
+        let mut highs = 0;
+        let mut lows = 0;
+        for _ in 0..iterations {
+            let (next, (h, l)) = press(system, &memory);
+            memory = next;
+            highs += h;
+            lows += l;
+        }
+        highs * lows
+    }
+
+    #[test]
+    fn part1_differential() {
+        let optimized = |(input, iterations): &(String, usize)| {
+            pulse_product(&Day20::parse(input).unwrap(), *iterations)
+        };
+        let reference = |(input, iterations): &(String, usize)| {
+            brute_force_pulses(&Day20::parse(input).unwrap(), *iterations)
+        };
+        differential::cross_check(200, generate_system, optimized, reference);
+        let generate = |rng: &mut Rng| (generate_counters(rng), rng.range(1..300) as usize);
+        differential::cross_check(50, generate, optimized, reference);
+    }
+
+    /// counters like the puzzle's: a chain of flip-flops counting the presses in binary, and a
+    /// conjunction sending a low pulse (and resetting the counter) once it reaches its period.
+    /// the conjunctions are inverted into the one in front of rx.
+    fn generate_counters(rng: &mut Rng) -> String {
+        let bits = rng.range(2..6);
+        let mut lines = vec![];
+        let mut starts = vec![];
+        for counter in 0..rng.range(1..4) {
+            // odd and with the highest bit set, so every flip-flop sends its pulses somewhere
+            let period = (1 << (bits - 1)) + 1 + 2 * rng.range(0..(1 << (bits - 2)).max(1));
+            let flip_flop = |bit: i64| format!("f{}_{}", counter, bit);
+            let conjunction = format!("c{}", counter);
+            let inverter = format!("i{}", counter);
+            let mut reset = vec![flip_flop(0)];
+            for bit in 0..bits {
+                let mut targets = vec![];
+                if bit + 1 < bits {
+                    targets.push(flip_flop(bit + 1));
+                }
+                if period & (1 << bit) != 0 {
+                    targets.push(conjunction.clone());
+                } else {
+                    reset.push(flip_flop(bit));
+                }
+                lines.push(format!("%{} -> {}", flip_flop(bit), targets.join(", ")));
+            }
+            reset.push(inverter.clone());
+            lines.push(format!("&{} -> {}", conjunction, reset.join(", ")));
+            lines.push(format!("&{} -> rx_proxy", inverter));
+            starts.push(flip_flop(0));
+        }
+        lines.push(format!("broadcaster -> {}", starts.join(", ")));
+        lines.push("&rx_proxy -> rx".to_string());
+        return lines.join("\n");
+    }
+
+    fn brute_force_presses(system: &System) -> Int {
+        let (ref mut states_flip_flop, ref mut states_conjunction) = init_memory(system);
+        let mut presses = 0;
+        loop {
+            presses += 1;
+            let mut low_to_rx = false;
+            push_button(
+                system,
+                states_flip_flop,
+                states_conjunction,
+                &mut |_, signal, receiver| {
+                    low_to_rx |= receiver == "rx" && signal == Signal::Low;
+                },
+            );
+            if low_to_rx {
+                return presses;
+            }
+        }
+    }
+
+    #[test]
+    fn part2_differential() {
+        differential::cross_check(
+            50,
+            generate_counters,
+            |input| Day20::part2(&Day20::parse(input).unwrap()),
+            |input| brute_force_presses(&Day20::parse(input).unwrap()).into(),
+        );
     }
 }
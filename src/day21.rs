@@ -1,163 +1,164 @@
+use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::{Params, Solution};
+use crate::utils::bfs::Bfs;
 use crate::utils::*;
-use std::collections::{HashMap, HashSet};
+use thiserror::Error;
 type Int = usize;
-type Map = HashMap<Point2D, Tile>;
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-enum Tile {
-    Garden,
-    Rocks,
+const PART1_STEPS: usize = 64;
+const PART2_STEPS: usize = 26501365;
+type Map = Grid<Tile>;
+type Garden = (Point2D, Map);
+
+pub struct Day21;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("unexpected tile {0:?}")]
+    UnexpectedTile(char),
+    #[error("missing start tile 'S'")]
+    MissingStart,
+    #[error(transparent)]
+    UnevenRow(#[from] UnevenRow),
+    #[error(transparent)]
+    EmptyGrid(#[from] EmptyGrid),
 }
 
-fn parse_input(input: &str) -> (Point2D, Map) {
-    let mut map = Map::new();
-    let mut start = Point2D::zero();
-    for (j, line) in input.trim().split('\n').enumerate() {
-        for (i, ch) in line.char_indices() {
-            let point = Point2D::new(i as i32, j as i32);
-            match ch {
-                '.' => {
-                    map.insert(point, Tile::Garden);
-                }
-                '#' => {
-                    map.insert(point, Tile::Rocks);
-                }
-                'S' => {
-                    map.insert(point, Tile::Garden);
-                    start = point;
-                }
-                u => panic!("unexpected character in input '{}'", u),
+impl Hint for ErrorKind {
+    fn hint(&self) -> Option<String> {
+        match self {
+            ErrorKind::UnexpectedTile(_) => {
+                Some("the map is made of garden plots '.', rocks '#' and the start 'S'".to_string())
             }
+            ErrorKind::MissingStart | ErrorKind::UnevenRow(_) | ErrorKind::EmptyGrid(_) => None,
         }
     }
-    (start, map)
 }
 
-type Coordinates = HashSet<Point2D>;
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Tile {
+    Garden,
+    Rocks,
+}
 
-fn main_loop<T>(
-    get_tile: T,
-    frontier_current: &mut Coordinates,
-    frontier_next: &mut Coordinates,
-    visited: &mut Coordinates,
-) where
-    T: FnOnce(Point2D) -> Option<Tile> + std::marker::Copy,
-{
-    for current in frontier_current.drain() {
-        for direction in Direction::ALL {
-            let next = current.translate(*direction);
-            if let Some(Tile::Garden) = get_tile(next) {
-                if visited.insert(next) {
-                    frontier_next.insert(next);
-                }
-            }
-        }
+fn parse_input(input: &str) -> Result<Garden, ParseError<ErrorKind>> {
+    let (map, starts) = Grid::parse_marked(input, Some('S'), |ch| match ch {
+        '.' | 'S' => Ok(Tile::Garden),
+        '#' => Ok(Tile::Rocks),
+        u => Err(ErrorKind::UnexpectedTile(u)),
+    })?;
+    match starts.first() {
+        Some(&start) => Ok((start, map)),
+        None => Err(ParseError::new(1, 1, ErrorKind::MissingStart)),
     }
 }
 
-fn solve<T, E, M>(steps: usize, start: &Point2D, get_tile: T, post_step: E, memory: &mut M) -> Int
+/// a bfs over the garden plots, `get_tile` tells what's at a position
+fn garden_walk<T>(
+    start: &Point2D,
+    get_tile: T,
+) -> Bfs<Point2D, impl FnMut(&Point2D) -> Vec<Point2D>>
 where
-    T: FnOnce(Point2D) -> Option<Tile> + std::marker::Copy,
-    E: FnOnce(usize, &Coordinates, &Coordinates, &Coordinates, &mut M) -> Option<Int>
-        + std::marker::Copy,
+    T: Fn(Point2D) -> Option<Tile>,
 {
-    let ref mut visited_even = Coordinates::new();
-    let ref mut visited_odd = Coordinates::new();
-    let ref mut frontier_even = Coordinates::new();
-    let ref mut frontier_odd = Coordinates::new();
-
-    frontier_even.insert(*start);
-
-    for i in 0..steps {
-        let result = if i % 2 == 0 {
-            main_loop(get_tile, frontier_even, frontier_odd, visited_even);
-            post_step(i + 1, visited_even, visited_odd, frontier_odd, memory)
-        } else {
-            main_loop(get_tile, frontier_odd, frontier_even, visited_odd);
-            post_step(i + 1, visited_odd, visited_even, frontier_even, memory)
-        };
-        if let Some(value) = result {
-            return value;
-        }
-    }
-
-    panic!("no result!")
+    Bfs::new([*start], move |current: &Point2D| {
+        Direction::ALL
+            .iter()
+            .map(|&direction| current.translate(direction))
+            .filter(|&next| get_tile(next) == Some(Tile::Garden))
+            .collect()
+    })
 }
 
-fn part1(input: &str, steps: usize) -> Int {
-    let (start, map) = parse_input(input);
-
-    solve(
-        steps,
-        &start,
-        |p| map.get(&p).map(|x| *x),
-        |s, visited, _, _, _| {
-            if s == steps {
-                Some(visited.len() as Int)
-            } else {
-                None
-            }
-        },
-        &mut "".to_string(),
-    )
+fn reachable(garden: &Garden, steps: usize) -> Int {
+    let (start, map) = garden;
+
+    let mut walk = garden_walk(start, |p| map.get(p).copied());
+    walk.run(steps, |_| None::<()>);
+    walk.reachable_in(steps)
 }
 
 fn from_repeated(p: &Point2D, mod_x: i32, mod_y: i32) -> Point2D {
     Point2D::new(positive_modulo(p.x, mod_x), positive_modulo(p.y, mod_y))
 }
 
-fn part2(input: &str, steps: usize) -> Int {
-    let (start, map) = parse_input(input);
-    let max_x = map.keys().map(|p| p.x as usize).max().unwrap() + 1;
-    let max_y = map.keys().map(|p| p.y).max().unwrap() + 1;
-
-    let ref mut memory = (
-        (0..max_x).map(|_| 0).collect::<Vec<i64>>(),
-        (0..max_x).map(|_| 0).collect::<Vec<i64>>(),
-        (0..max_x).map(|_| 0).collect::<Vec<i64>>(),
-    );
-    solve(
-        steps,
-        &start,
-        |p| map.get(&from_repeated(&p, max_x as i32, max_y)).map(|x| *x),
-        |step, visited, visited_prev, frontier, mem| {
-            let ref mut frontiers = mem.0;
-            let ref mut frontier_increments = mem.1;
-            let ref mut frontier_jitter = mem.2;
-
-            if step == steps {
-                return Some(visited.len());
-            }
+fn reachable_repeated(garden: &Garden, steps: usize) -> Int {
+    walk_repeated(garden, steps).0
+}
 
-            let fsize = frontier.len() as i64;
-            let ix = (step - 1) % max_x;
-            if step > max_x {
-                frontier_jitter[ix] = fsize - frontiers[ix] - frontier_increments[ix];
-                frontier_increments[ix] = fsize - frontiers[ix];
-            }
-            frontiers[ix] = fsize;
-
-            if step >= 2 * max_x && frontier_jitter.iter().all(|&i| i == 0) {
-                let mut result_prev = visited_prev.len();
-                let mut result = visited.len();
-                for s in step..steps {
-                    let ix = s % max_x;
-                    frontiers[ix] += frontier_increments[ix];
-                    let result_next = result_prev + frontiers[ix] as usize;
-                    result_prev = result;
-                    result = result_next;
-                }
-                return Some(result);
+/// the plots reachable on the repeated map, and whether the count was extrapolated
+fn walk_repeated(garden: &Garden, steps: usize) -> (Int, bool) {
+    let (start, map) = garden;
+    let max_x = map.width();
+    let max_y = map.height() as i32;
+
+    let frontiers = &mut vec![0_i64; max_x];
+    let frontier_increments = &mut vec![0_i64; max_x];
+    let frontier_jitter = &mut vec![0_i64; max_x];
+    let mut walk = garden_walk(start, |p| {
+        map.get(from_repeated(&p, max_x as i32, max_y)).copied()
+    });
+    let extrapolated = walk.run(steps, |walk| {
+        let step = walk.step();
+        let fsize = walk.frontier().len() as i64;
+        let ix = (step - 1) % max_x;
+        if step > max_x {
+            frontier_jitter[ix] = fsize - frontiers[ix] - frontier_increments[ix];
+            frontier_increments[ix] = fsize - frontiers[ix];
+        }
+        frontiers[ix] = fsize;
+
+        if step >= 2 * max_x && frontier_jitter.iter().all(|&i| i == 0) {
+            let mut result_prev = walk.reachable_in(step - 1);
+            let mut result = walk.reachable_in(step);
+            for s in step..steps {
+                let ix = s % max_x;
+                frontiers[ix] += frontier_increments[ix];
+                let result_next = result_prev + frontiers[ix] as usize;
+                result_prev = result;
+                result = result_next;
             }
-            return None;
-        },
-        memory,
-    )
+            return Some(result);
+        }
+        return None;
+    });
+    // small step counts are over before the frontier growth settles
+    match extrapolated {
+        Some(x) => (x, true),
+        None => (walk.reachable_in(steps), false),
+    }
+}
+
+impl Solution for Day21 {
+    type Parsed = Garden;
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        parse_input(input)
+    }
+
+    fn part1(garden: &Self::Parsed) -> Answer {
+        reachable(garden, PART1_STEPS).into()
+    }
+
+    fn part2(garden: &Self::Parsed) -> Answer {
+        reachable_repeated(garden, PART2_STEPS).into()
+    }
+
+    fn part1_with(garden: &Self::Parsed, params: &Params) -> Answer {
+        reachable(garden, params.get("steps", PART1_STEPS)).into()
+    }
+
+    fn part2_with(garden: &Self::Parsed, params: &Params) -> Answer {
+        reachable_repeated(garden, params.get("steps", PART2_STEPS)).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::utils;
+    use crate::utils::differential::{self, Rng};
 
     const EXAMPLE: &str = r#"
 ...........
@@ -175,55 +176,143 @@ mod tests {
 
     #[test]
     fn part1_example1() {
-        let result = part1(EXAMPLE, 6);
+        let result = reachable(&Day21::parse(EXAMPLE).unwrap(), 6);
         assert_eq!(result, 16)
     }
 
     #[test]
     fn part1_result() {
         let input = utils::resource("src/day21.txt");
-        let result = part1(&input, 64);
+        let result = Day21::part1(&Day21::parse(&input).unwrap());
         assert_eq!(result, 3830);
     }
 
     #[test]
     fn part2_example_6() {
-        assert_eq!(part2(&EXAMPLE, 6), 16);
+        assert_eq!(reachable_repeated(&Day21::parse(EXAMPLE).unwrap(), 6), 16);
     }
 
     #[test]
     fn part2_example_10() {
-        assert_eq!(part2(&EXAMPLE, 10), 50);
+        assert_eq!(reachable_repeated(&Day21::parse(EXAMPLE).unwrap(), 10), 50);
     }
 
     #[test]
     fn part2_example_50() {
-        assert_eq!(part2(&EXAMPLE, 50), 1594);
+        assert_eq!(
+            reachable_repeated(&Day21::parse(EXAMPLE).unwrap(), 50),
+            1594
+        );
     }
 
     #[test]
     fn part2_example_100() {
-        assert_eq!(part2(&EXAMPLE, 100), 6536);
+        assert_eq!(
+            reachable_repeated(&Day21::parse(EXAMPLE).unwrap(), 100),
+            6536
+        );
     }
 
     #[test]
     fn part2_example_500() {
-        assert_eq!(part2(&EXAMPLE, 500), 167004);
+        assert_eq!(
+            reachable_repeated(&Day21::parse(EXAMPLE).unwrap(), 500),
+            167004
+        );
     }
 
     #[test]
     fn part2_example_1000() {
-        assert_eq!(part2(&EXAMPLE, 1000), 668697);
+        assert_eq!(
+            reachable_repeated(&Day21::parse(EXAMPLE).unwrap(), 1000),
+            668697
+        );
     }
 
     #[test]
     fn part2_example_5000() {
-        assert_eq!(part2(&EXAMPLE, 5000), 16733044);
+        assert_eq!(
+            reachable_repeated(&Day21::parse(EXAMPLE).unwrap(), 5000),
+            16733044
+        );
     }
 
     #[test]
     fn part2_result() {
         let input = utils::resource("src/day21.txt");
-        assert_eq!(part2(&input, 26501365), 637087163925555);
+        assert_eq!(
+            Day21::part2(&Day21::parse(&input).unwrap()),
+            637087163925555_i64
+        );
+    }
+
+    #[test]
+    fn parse_error() {
+        let result = Day21::parse("...\n.S.\n.O.");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(3, 2, ErrorKind::UnexpectedTile('O')))
+        );
+        let result = Day21::parse("...\n.#.");
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(1, 1, ErrorKind::MissingStart))
+        );
+    }
+
+    /// a small square garden shaped like the puzzle's, and a number of steps past the point
+    /// where the frontier growth has settled (about three times the size for these)
+    fn generate(rng: &mut Rng) -> (String, usize) {
+        let size = 2 * rng.range(2..5) + 1;
+        let mid = size / 2;
+        // like the puzzle, the border and the start's row and column are clear
+        let clear = |x, y| [x, y].iter().any(|&c| c == 0 || c == mid || c == size - 1);
+        let rows: Vec<String> = (0..size)
+            .map(|y| {
+                (0..size)
+                    .map(|x| match (x, y) {
+                        (x, y) if (x, y) == (mid, mid) => 'S',
+                        (x, y) if clear(x, y) => '.',
+                        _ if rng.chance(15) => '#',
+                        _ => '.',
+                    })
+                    .collect()
+            })
+            .collect();
+        return (rows.join("\n"), rng.range(4 * size..10 * size) as usize);
+    }
+
+    /// every plot reachable after each step, on the infinitely repeated map
+    fn brute_force((start, map): &Garden, steps: usize) -> Int {
+        let (width, height) = (map.width() as i32, map.height() as i32);
+        let mut plots = std::collections::HashSet::from([*start]);
+        for _ in 0..steps {
+            plots = plots
+                .iter()
+                .flat_map(|p| Direction::ALL.iter().map(|&d| p.translate(d)))
+                .filter(|&p| map[from_repeated(&p, width, height)] == Tile::Garden)
+                .collect();
+        }
+        return plots.len();
+    }
+
+    #[test]
+    fn part2_differential() {
+        differential::cross_check(
+            100,
+            generate,
+            |(input, steps)| {
+                let (reachable, extrapolated) =
+                    walk_repeated(&Day21::parse(input).unwrap(), *steps);
+                // the shortcut is what's being checked, not the plain walk
+                assert!(
+                    extrapolated,
+                    "{} steps were walked without extrapolating",
+                    steps
+                );
+                reachable
+            },
+            |(input, steps)| brute_force(&Day21::parse(input).unwrap(), *steps),
+        );
     }
 }
@@ -0,0 +1,189 @@
+use std::fmt::{Debug, Display};
+
+/// A parser gave up at `line` and `column` (both 1-based) of the input, `kind` says why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError<K> {
+    pub line: usize,
+    pub column: usize,
+    pub kind: K,
+}
+
+impl<K> ParseError<K> {
+    pub fn new(line: usize, column: usize, kind: K) -> ParseError<K> {
+        ParseError { line, column, kind }
+    }
+
+    /// An error pointing at `part`, which has to be a subslice of the `text` of the `line`.
+    pub fn at(line: usize, text: &str, part: &str, kind: K) -> ParseError<K> {
+        let offset = (part.as_ptr() as usize).saturating_sub(text.as_ptr() as usize);
+        let offset = offset.min(text.len());
+        let column = text[..offset].chars().count() + 1;
+        ParseError::new(line, column, kind)
+    }
+
+    /// An error pointing just past the end of the `text` of the `line`.
+    pub fn after(line: usize, text: &str, kind: K) -> ParseError<K> {
+        ParseError::new(line, text.chars().count() + 1, kind)
+    }
+
+    pub fn map<T, F>(self, f: F) -> ParseError<T>
+    where
+        F: FnOnce(K) -> T,
+    {
+        ParseError::new(self.line, self.column, f(self.kind))
+    }
+}
+
+impl<K: Display + Hint> ParseError<K> {
+    /// The error together with the offending line of `source` and a caret under the column.
+    /// `origin` names the input, usually its path.
+    ///
+    /// ```text
+    /// error: unexpected tile '#'
+    ///  --> src/day16.txt:2:8
+    ///   |
+    /// 2 | |.-.\..#..
+    ///   |        ^
+    ///   = hint: the contraption is made of empty space '.', mirrors '/' '\' and splitters '|' '-'
+    /// ```
+    pub fn render(&self, source: &str, origin: &str) -> String {
+        let mut out = format!("error: {}\n", self.kind);
+        let gutter = " ".repeat(self.line.to_string().len());
+        out += &format!("{}--> {}:{}:{}\n", gutter, origin, self.line, self.column);
+
+        let mut hint = self.kind.hint();
+        let excerpt = match self.line {
+            0 => None,
+            x => source.split('\n').nth(x - 1),
+        };
+        if let Some(raw) = excerpt {
+            let text = raw.strip_suffix('\r').unwrap_or(raw);
+            if text.len() != raw.len() && self.column > text.chars().count() {
+                // pointing at the '\r', whatever the day expected there, the real problem is the line ending
+                hint =
+                    Some("the input has Windows (CRLF) line endings, convert it to LF".to_string());
+            }
+            // tabs would throw off the caret
+            let text = text.replace('\t', " ");
+            out += &format!("{} |\n", gutter);
+            out += &format!("{} | {}\n", self.line, text);
+            out += &format!(
+                "{} | {}^\n",
+                gutter,
+                " ".repeat(self.column.saturating_sub(1))
+            );
+        }
+        if let Some(hint) = hint {
+            out += &format!("{} = hint: {}\n", gutter, hint);
+        }
+        return out;
+    }
+}
+
+/// Something to add to an error beyond what went wrong, usually what the input should look like.
+pub trait Hint {
+    fn hint(&self) -> Option<String> {
+        None
+    }
+}
+
+/// The text and hint of an error kind, so the errors of all the days have the same type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub text: String,
+    pub hint: Option<String>,
+}
+
+impl Message {
+    pub fn of<K: Display + Hint>(kind: &K) -> Message {
+        Message {
+            text: kind.to_string(),
+            hint: kind.hint(),
+        }
+    }
+}
+
+impl Display for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl Hint for Message {
+    fn hint(&self) -> Option<String> {
+        self.hint.clone()
+    }
+}
+
+impl<K: Display> Display for ParseError<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.kind
+        )
+    }
+}
+
+impl<K: Display + Debug> std::error::Error for ParseError<K> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn at_subslice() {
+        let text = "%a -> b";
+        let error = ParseError::at(3, text, &text[3..5], "arrow");
+        assert_eq!(error, ParseError::new(3, 4, "arrow"));
+        assert_eq!(error.to_string(), "line 3, column 4: arrow");
+        assert_eq!(ParseError::after(1, text, ()).column, 8);
+    }
+
+    fn message(text: &str, hint: Option<&str>) -> Message {
+        Message {
+            text: text.to_string(),
+            hint: hint.map(String::from),
+        }
+    }
+
+    #[test]
+    fn render_excerpt() {
+        let source = "broadcaster -> a\n$a -> b\n";
+        let kind = message(
+            "unknown module type '$'",
+            Some("modules look like '%a -> b'"),
+        );
+        let rendered = ParseError::new(2, 1, kind).render(source, "day20.txt");
+        let expected = r#"error: unknown module type '$'
+ --> day20.txt:2:1
+  |
+2 | $a -> b
+  | ^
+  = hint: modules look like '%a -> b'
+"#;
+        assert_eq!(rendered, expected);
+    }
+
+    #[test]
+    fn render_crlf() {
+        let source = "..F7.\r\n.FJ|.\r\n";
+        let kind = message("unexpected tile '\\r'", Some("pipes are '|-LJ7F'"));
+        let rendered = ParseError::new(1, 6, kind).render(source, "day10.txt");
+        let expected = r#"error: unexpected tile '\r'
+ --> day10.txt:1:6
+  |
+1 | ..F7.
+  |      ^
+  = hint: the input has Windows (CRLF) line endings, convert it to LF
+"#;
+        assert_eq!(rendered, expected);
+    }
+
+    #[test]
+    fn render_without_excerpt() {
+        let kind = message("missing start tile 'S'", None);
+        let rendered = ParseError::new(12, 1, kind).render("...", "-");
+        assert_eq!(rendered, "error: missing start tile 'S'\n  --> -:12:1\n");
+    }
+}
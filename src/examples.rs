@@ -0,0 +1,129 @@
+//! Example fixtures: `src/dayNN.example.txt` or `src/dayNN.example.N.txt`, next to the day's input.
+//!
+//! The expected answers live in a sidecar with the same name but a `.json` extension, a list of
+//! cases, each with the answers of (some of) the parts and the [`Params`] they need:
+//!
+//! ```json
+//! [
+//!   { "params": { "steps": 6 }, "part1": 16, "part2": 16 },
+//!   { "params": { "steps": 10 }, "part2": 50 }
+//! ]
+//! ```
+//!
+//! `build.rs` generates a test for every fixture, so adding an example takes no Rust at all.
+use crate::answer::Answer;
+use crate::solution::{self, Params};
+use crate::utils;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Case {
+    #[serde(default)]
+    pub params: Params,
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+}
+
+/// The day of a fixture named `dayNN.example.txt` or `dayNN.example.N.txt`.
+pub fn fixture_day(file_name: &str) -> Option<u8> {
+    let rest = file_name.strip_prefix("day")?;
+    let (day, rest) = rest.split_at_checked(2)?;
+    let rest = rest.strip_prefix(".example")?.strip_suffix(".txt")?;
+    let valid_index = match rest.strip_prefix('.') {
+        Some(index) => !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()),
+        None => rest.is_empty(),
+    };
+    return match valid_index {
+        true => day.parse().ok(),
+        false => None,
+    };
+}
+
+/// All the fixtures in `dir`, ordered by name.
+pub fn discover(dir: &Path) -> Vec<PathBuf> {
+    let mut fixtures: Vec<PathBuf> = match dir.read_dir() {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                let name = path.file_name().and_then(|x| x.to_str());
+                name.and_then(fixture_day).is_some()
+            })
+            .collect(),
+        Err(_) => vec![],
+    };
+    fixtures.sort();
+    return fixtures;
+}
+
+/// Solves every case of the `fixture` (a path relative to the crate root), the mismatches are returned as messages.
+pub fn check(fixture: &str) -> Result<(), Vec<String>> {
+    let name = Path::new(fixture).file_name().and_then(|x| x.to_str());
+    let day = name
+        .and_then(fixture_day)
+        .ok_or(vec![format!("{}: not a fixture", fixture)])?;
+    let sidecar = utils::resolve(fixture).with_extension("json");
+    let cases: Vec<Case> = utils::read_input(&sidecar)
+        .map_err(|e| e.to_string())
+        .and_then(|text| {
+            serde_json::from_str(&text).map_err(|e| format!("{}: {}", sidecar.display(), e))
+        })
+        .map_err(|e| vec![e])?;
+
+    let solver = solution::solver(day).ok_or(vec![format!("day {} is not implemented", day)])?;
+    let input = utils::read_input(utils::resolve(fixture)).map_err(|e| vec![e.to_string()])?;
+    let prepared = solver
+        .prepare(&input)
+        .map_err(|e| vec![e.render(&input, fixture)])?;
+
+    let mut mismatches = vec![];
+    for case in cases.iter() {
+        for (part, expected) in [(1, &case.part1), (2, &case.part2)] {
+            let Some(expected) = expected else {
+                continue;
+            };
+            let actual = prepared
+                .part_with(part, &case.params)
+                .expect("all days have two parts");
+            if &actual != expected {
+                mismatches.push(format!(
+                    "{} part{} {:?}: expected {}, got {}",
+                    fixture, part, case.params.0, expected, actual
+                ));
+            }
+        }
+    }
+    return match mismatches.is_empty() {
+        true => Ok(()),
+        false => Err(mismatches),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_example(fixture: &str) {
+        if let Err(mismatches) = check(fixture) {
+            panic!("\n{}", mismatches.join("\n"));
+        }
+    }
+
+    #[test]
+    fn fixture_names() {
+        assert_eq!(fixture_day("day05.example.txt"), Some(5));
+        assert_eq!(fixture_day("day20.example.2.txt"), Some(20));
+        assert_eq!(fixture_day("day20.example.txt.json"), None);
+        assert_eq!(fixture_day("day20.example..txt"), None);
+        assert_eq!(fixture_day("day20.txt"), None);
+    }
+
+    #[test]
+    fn discovers_fixtures() {
+        let fixtures = discover(&utils::resolve("src"));
+        assert!(fixtures.iter().any(|x| x.ends_with("day05.example.txt")));
+    }
+
+    // a `#[test]` calling `assert_example` for every fixture
+    include!(concat!(env!("OUT_DIR"), "/example_tests.rs"));
+}
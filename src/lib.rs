@@ -0,0 +1,33 @@
+//! Advent of Code 2023 solvers.
+//!
+//! Every `dayNN` module implements [`solution::Solution`] for its `DayNN` type, all of them are
+//! listed in [`solution::REGISTRY`]. [`utils`] has the grid helpers shared between the days.
+pub mod answer;
+pub mod bench;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod error;
+pub mod examples;
+pub mod scaffold;
+pub mod solution;
+pub mod utils;
+pub mod verify;
@@ -1,29 +1,346 @@
-#![allow(dead_code)]
-#[macro_use]
-extern crate impl_ops; // https://stackoverflow.com/a/57021762/155005
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod utils;
-
-fn main() {
-    println!("Hello, world!");
+use aoc_2023::answer::Answer;
+use aoc_2023::verify::{self, Outcome};
+use aoc_2023::{bench, scaffold, solution, utils};
+use std::path::PathBuf;
+use std::time::Duration;
+use std::{env, process::ExitCode};
+
+const USAGE: &str = "usage:
+  aoc-2023 <day> [<part>] [--input <path>]
+  aoc-2023 bench [<day>...] [--runs <n>] [--baseline <path>] [--save-baseline <path>] [--threshold <percent>]
+  aoc-2023 verify [--answers <path>]
+  aoc-2023 new <day>";
+
+enum Command {
+    Run(Args),
+    Bench(BenchArgs),
+    Verify { answers: String },
+    New { day: u8 },
+}
+
+struct Args {
+    day: u8,
+    parts: Vec<u8>,
+    input: Option<String>,
+}
+
+struct BenchArgs {
+    days: Vec<u8>,
+    runs: usize,
+    baseline: Option<PathBuf>,
+    save_baseline: Option<PathBuf>,
+    threshold: f64,
+}
+
+fn parse_command(args: &[String]) -> Result<Command, String> {
+    return match args.first().map(|x| x.as_str()) {
+        Some("bench") => parse_bench_args(&args[1..]).map(Command::Bench),
+        Some("verify") => match &args[1..] {
+            [] => Ok(Command::Verify {
+                answers: utils::resolve(verify::KNOWN_ANSWERS).display().to_string(),
+            }),
+            [option, path] if option == "--answers" => Ok(Command::Verify {
+                answers: path.clone(),
+            }),
+            [x, ..] => Err(format!("unexpected argument '{}'", x)),
+        },
+        Some("new") => match &args[1..] {
+            [day] => parse_day(day).map(|day| Command::New { day }),
+            [] => Err("missing day".to_string()),
+            [_, x, ..] => Err(format!("unexpected argument '{}'", x)),
+        },
+        _ => parse_args(args).map(Command::Run),
+    };
+}
+
+fn parse_day(x: &str) -> Result<u8, String> {
+    return x.parse::<u8>().map_err(|_| format!("invalid day '{}'", x));
+}
+
+fn parse_bench_args(args: &[String]) -> Result<BenchArgs, String> {
+    let mut bench_args = BenchArgs {
+        days: vec![],
+        runs: 10,
+        baseline: None,
+        save_baseline: None,
+        threshold: 0.2,
+    };
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || {
+            iter.next()
+                .ok_or_else(|| format!("{} requires a value", arg))
+        };
+        match arg.as_str() {
+            "--runs" => {
+                let runs = value()?;
+                bench_args.runs = match runs.parse::<usize>() {
+                    Ok(x) if x > 0 => x,
+                    _ => return Err(format!("invalid number of runs '{}'", runs)),
+                };
+            }
+            "--baseline" => bench_args.baseline = Some(PathBuf::from(value()?)),
+            "--save-baseline" => bench_args.save_baseline = Some(PathBuf::from(value()?)),
+            "--threshold" => {
+                let threshold = value()?;
+                bench_args.threshold = match threshold.parse::<f64>() {
+                    Ok(x) if x >= 0.0 => x / 100.0,
+                    _ => return Err(format!("invalid threshold '{}'", threshold)),
+                };
+            }
+            x if x.starts_with('-') => return Err(format!("unknown option '{}'", x)),
+            x => bench_args.days.push(parse_day(x)?),
+        }
+    }
+    if bench_args.days.is_empty() {
+        bench_args.days = solution::REGISTRY.iter().map(|(day, _)| *day).collect();
+    }
+    return Ok(bench_args);
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut positional = Vec::<&str>::new();
+    let mut input = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--input" | "-i" => match iter.next() {
+                Some(path) => input = Some(path.clone()),
+                None => return Err("--input requires a path".to_string()),
+            },
+            x if x.starts_with('-') => return Err(format!("unknown option '{}'", x)),
+            x => positional.push(x),
+        }
+    }
+
+    let day = match positional.first() {
+        Some(x) => parse_day(x)?,
+        None => return Err("missing day".to_string()),
+    };
+    let parts = match positional.get(1) {
+        Some(&"1") => vec![1],
+        Some(&"2") => vec![2],
+        Some(x) => return Err(format!("invalid part '{}', expected 1 or 2", x)),
+        None => vec![1, 2],
+    };
+    if positional.len() > 2 {
+        return Err(format!("unexpected argument '{}'", positional[2]));
+    }
+
+    return Ok(Args { day, parts, input });
+}
+
+fn warn_changes(path: &str, changes: &[utils::Normalization]) {
+    for change in changes {
+        eprintln!("warning: {}: {}", path, change);
+    }
+}
+
+fn run(args: Args) -> ExitCode {
+    let solver = match solution::solver(args.day) {
+        Some(x) => x,
+        None => {
+            eprintln!("day {} is not implemented", args.day);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let path = match &args.input {
+        Some(path) => path.clone(),
+        None => utils::day_input_path(args.day).display().to_string(),
+    };
+    let input = match utils::load_input(&path) {
+        Ok((input, changes)) => {
+            warn_changes(&path, &changes);
+            input
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let prepared = match solver.prepare(&input) {
+        Ok(x) => x,
+        Err(e) => {
+            eprint!("{}", e.render(&input, &path));
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut status = ExitCode::SUCCESS;
+    for part in args.parts {
+        match prepared.part(part).expect("all days have two parts") {
+            answer @ Answer::Unsolvable(_) => {
+                eprintln!("day{:02} part{}: {}", args.day, part, answer);
+                status = ExitCode::FAILURE;
+            }
+            answer => println!("day{:02} part{}: {}", args.day, part, answer),
+        }
+    }
+    return status;
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn run_bench(args: BenchArgs) -> ExitCode {
+    let baseline = match &args.baseline {
+        Some(path) => match bench::load_baseline(path) {
+            Ok(x) => x,
+            Err(e) => {
+                eprintln!("{}: {}", path.display(), e);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => bench::Baseline::new(),
+    };
+    let mut medians = bench::Baseline::new();
+    let mut failed = false;
+
+    println!(
+        "{:<6}{:<7}{:>13}{:>13}{:>13}",
+        "day", "step", "min", "median", "max"
+    );
+    for day in args.days {
+        let solver = match solution::solver(day) {
+            Some(x) => x,
+            None => {
+                eprintln!("day {} is not implemented", day);
+                failed = true;
+                continue;
+            }
+        };
+        let path = utils::day_input_path(day);
+        let input = match utils::load_input(&path) {
+            Ok((input, changes)) => {
+                warn_changes(&path.display().to_string(), &changes);
+                input
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                failed = true;
+                continue;
+            }
+        };
+        let timings = match bench::bench_day(solver, &input, args.runs) {
+            Ok(x) => x,
+            Err(e) => {
+                eprint!("{}", e.render(&input, &path.display().to_string()));
+                failed = true;
+                continue;
+            }
+        };
+
+        for (step, timing) in bench::STEPS.iter().zip(timings) {
+            let key = bench::baseline_key(day, step);
+            let mut flag = String::new();
+            if let Some(&nanos) = baseline.get(&key) {
+                let before = Duration::from_nanos(nanos);
+                if let Some(slowdown) = bench::regression(before, timing.median, args.threshold) {
+                    flag = format!(
+                        "  REGRESSION +{:.0}% (was {})",
+                        slowdown * 100.0,
+                        millis(before)
+                    );
+                    failed = true;
+                }
+            }
+            println!(
+                "{:<6}{:<7}{:>13}{:>13}{:>13}{}",
+                format!("{:02}", day),
+                step,
+                millis(timing.min),
+                millis(timing.median),
+                millis(timing.max),
+                flag
+            );
+            medians.insert(key, timing.median.as_nanos() as u64);
+        }
+    }
+
+    if let Some(path) = &args.save_baseline {
+        if let Err(e) = bench::save_baseline(path, &medians) {
+            eprintln!("{}: {}", path.display(), e);
+            return ExitCode::FAILURE;
+        }
+    }
+    return match failed {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    };
+}
+
+fn run_verify(answers: &str) -> ExitCode {
+    let known = match verify::load_known_answers(answers) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("{}: {}", answers, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut passed = 0;
+    let mut total = 0;
+    println!(
+        "{:<5}{:<6}{:<24}{:>20}{:>20}  result",
+        "day", "part", "input", "expected", "actual"
+    );
+    for check in known.iter().flat_map(verify::verify) {
+        let actual = match &check.outcome {
+            Outcome::Pass => check.expected.to_string(),
+            Outcome::Fail(actual) => actual.to_string(),
+            Outcome::Error(_) => "-".to_string(),
+        };
+        println!(
+            "{:<5}{:<6}{:<24}{:>20}{:>20}  {}",
+            format!("{:02}", check.day),
+            check.part,
+            check.input,
+            check.expected,
+            actual,
+            check.outcome
+        );
+        if let Outcome::Error(message) = &check.outcome {
+            println!("     {}", message);
+        }
+        total += 1;
+        if check.outcome == Outcome::Pass {
+            passed += 1;
+        }
+    }
+    println!("{}/{} passed", passed, total);
+    return match passed == total {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    };
+}
+
+fn run_new(day: u8) -> ExitCode {
+    match scaffold::scaffold(&utils::resolve(""), day) {
+        Ok(changed) => {
+            for path in changed {
+                println!("{}", path.display());
+            }
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    return match parse_command(&args) {
+        Ok(Command::Run(args)) => run(args),
+        Ok(Command::Bench(args)) => run_bench(args),
+        Ok(Command::Verify { answers }) => run_verify(&answers),
+        Ok(Command::New { day }) => run_new(day),
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            ExitCode::from(2)
+        }
+    };
 }
@@ -0,0 +1,261 @@
+//! Generates a new day, for `aoc-2023 new <day>`.
+//!
+//! Creates `src/dayNN.rs` from a template (parsing lines, both parts and the usual tests), an empty
+//! `src/dayNN.txt` input, an example fixture with its sidecar (see [`crate::examples`]), and
+//! registers the module in `src/lib.rs` and [`crate::solution::REGISTRY`].
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ScaffoldError {
+    #[error("invalid day {0}, expected 1 to 25")]
+    InvalidDay(u8),
+    #[error("'{}' already exists", .0.display())]
+    AlreadyExists(PathBuf),
+    #[error("no place to register day {day} in '{}'", path.display())]
+    NotRegistrable { day: u8, path: PathBuf },
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+const TEMPLATE: &str = r#"use crate::answer::Answer;
+use crate::error::{Hint, ParseError};
+use crate::solution::Solution;
+use crate::utils::parse;
+use thiserror::Error;
+
+pub struct DayNN;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[error("unexpected input")]
+    UnexpectedInput,
+}
+
+impl Hint for ErrorKind {}
+
+impl Solution for DayNN {
+    type Parsed = Vec<String>;
+    type ErrorKind = ErrorKind;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<ErrorKind>> {
+        Ok(parse::lines(input)
+            .map(|line| line.text.to_string())
+            .collect())
+    }
+
+    fn part1(lines: &Self::Parsed) -> Answer {
+        lines.len().into()
+    }
+
+    fn part2(lines: &Self::Parsed) -> Answer {
+        lines.len().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::resource;
+
+    const EXAMPLE: &str = "";
+
+    #[test]
+    fn part1_example() {
+        let result = DayNN::part1(&DayNN::parse(EXAMPLE).unwrap());
+        assert_eq!(result, 0);
+    }
+
+    #[test]
+    fn part1_result() {
+        let input = resource("src/dayNN.txt");
+        let result = DayNN::part1(&DayNN::parse(&input).unwrap());
+        assert_eq!(result, 0);
+    }
+
+    #[test]
+    fn part2_example() {
+        let result = DayNN::part2(&DayNN::parse(EXAMPLE).unwrap());
+        assert_eq!(result, 0);
+    }
+
+    #[test]
+    fn part2_result() {
+        let input = resource("src/dayNN.txt");
+        let result = DayNN::part2(&DayNN::parse(&input).unwrap());
+        assert_eq!(result, 0);
+    }
+}
+"#;
+
+const SIDECAR: &str = r#"[
+  { "part1": null, "part2": null }
+]
+"#;
+
+/// The source of `src/dayNN.rs`.
+pub fn module_source(day: u8) -> String {
+    TEMPLATE.replace("NN", &format!("{:02}", day))
+}
+
+/// Inserts `entry` for `day` among the `lines` for other days, keeping them ordered.
+/// `day_of` tells which day a line registers, if any.
+fn insert_ordered<F>(text: &str, day: u8, entry: &str, day_of: F) -> Option<String>
+where
+    F: Fn(&str) -> Option<u8>,
+{
+    let lines: Vec<&str> = text.lines().collect();
+    let registered: Vec<(usize, u8)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| day_of(line).map(|d| (i, d)))
+        .collect();
+    if registered.iter().any(|&(_, d)| d == day) {
+        return None;
+    }
+    let at = match registered.iter().find(|&&(_, d)| d > day) {
+        Some(&(i, _)) => i,
+        None => registered.last()?.0 + 1,
+    };
+
+    let mut result: Vec<&str> = lines[..at].to_vec();
+    result.push(entry);
+    result.extend(&lines[at..]);
+    return Some(result.join("\n") + "\n");
+}
+
+/// `src/lib.rs` with a `pub mod dayNN;` for `day`.
+pub fn register_module(lib_rs: &str, day: u8) -> Option<String> {
+    let entry = format!("pub mod day{:02};", day);
+    return insert_ordered(lib_rs, day, &entry, |line| {
+        line.strip_prefix("pub mod day")?
+            .strip_suffix(';')?
+            .parse()
+            .ok()
+    });
+}
+
+/// `src/solution.rs` with `day` in the registry.
+pub fn register_solver(solution_rs: &str, day: u8) -> Option<String> {
+    let entry = format!("    ({0}, &crate::day{0:02}::Day{0:02}),", day);
+    return insert_ordered(solution_rs, day, &entry, |line| {
+        let (day, rest) = line.trim().strip_prefix('(')?.split_once(',')?;
+        rest.trim().strip_prefix("&crate::day")?;
+        day.parse().ok()
+    });
+}
+
+fn create(path: PathBuf, contents: &str) -> Result<PathBuf, ScaffoldError> {
+    if path.exists() {
+        return Err(ScaffoldError::AlreadyExists(path));
+    }
+    fs::write(&path, contents)?;
+    return Ok(path);
+}
+
+fn update<F>(path: PathBuf, day: u8, register: F) -> Result<PathBuf, ScaffoldError>
+where
+    F: Fn(&str, u8) -> Option<String>,
+{
+    let text = fs::read_to_string(&path)?;
+    match register(&text, day) {
+        Some(updated) => fs::write(&path, updated)?,
+        None => return Err(ScaffoldError::NotRegistrable { day, path }),
+    }
+    return Ok(path);
+}
+
+/// Scaffolds `day` in the crate at `root`, returns the files it created or changed.
+pub fn scaffold(root: &Path, day: u8) -> Result<Vec<PathBuf>, ScaffoldError> {
+    if !(1..=25).contains(&day) {
+        return Err(ScaffoldError::InvalidDay(day));
+    }
+    let src = root.join("src");
+    let name = format!("day{:02}", day);
+    let module = src.join(format!("{}.rs", name));
+    if module.exists() {
+        return Err(ScaffoldError::AlreadyExists(module));
+    }
+    // registering first, so nothing is left behind when the crate doesn't look as expected
+    let lib_rs = fs::read_to_string(src.join("lib.rs"))?;
+    let solution_rs = fs::read_to_string(src.join("solution.rs"))?;
+    if register_module(&lib_rs, day).is_none() {
+        let path = src.join("lib.rs");
+        return Err(ScaffoldError::NotRegistrable { day, path });
+    }
+    if register_solver(&solution_rs, day).is_none() {
+        let path = src.join("solution.rs");
+        return Err(ScaffoldError::NotRegistrable { day, path });
+    }
+
+    let mut changed = vec![];
+    for (file_name, contents) in [
+        (format!("{}.txt", name), ""),
+        (format!("{}.example.txt", name), ""),
+        (format!("{}.example.json", name), SIDECAR),
+    ] {
+        let path = src.join(file_name);
+        if !path.exists() {
+            changed.push(create(path, contents)?);
+        }
+    }
+    changed.push(create(module, &module_source(day))?);
+    changed.push(update(src.join("lib.rs"), day, register_module)?);
+    changed.push(update(src.join("solution.rs"), day, register_solver)?);
+    return Ok(changed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::resolve;
+
+    #[test]
+    fn registers_in_order() {
+        let lib_rs = "pub mod day01;\npub mod day03;\npub mod error;\n";
+        let registered = register_module(lib_rs, 2).unwrap();
+        assert_eq!(
+            registered,
+            "pub mod day01;\npub mod day02;\npub mod day03;\npub mod error;\n"
+        );
+        let registered = register_module(lib_rs, 22).unwrap();
+        assert_eq!(
+            registered,
+            "pub mod day01;\npub mod day03;\npub mod day22;\npub mod error;\n"
+        );
+        assert_eq!(register_module(lib_rs, 3), None);
+    }
+
+    #[test]
+    fn scaffolds_a_day() {
+        let root = std::env::temp_dir().join(format!("aoc-2023-scaffold-{}", std::process::id()));
+        let src = root.join("src");
+        fs::create_dir_all(&src).unwrap();
+        for file in ["src/lib.rs", "src/solution.rs"] {
+            fs::copy(resolve(file), root.join(file)).unwrap();
+        }
+
+        let changed = scaffold(&root, 22).unwrap();
+        assert_eq!(changed.len(), 6);
+        let lib_rs = fs::read_to_string(src.join("lib.rs")).unwrap();
+        assert!(lib_rs.contains("pub mod day21;\npub mod day22;\n"));
+        let solution_rs = fs::read_to_string(src.join("solution.rs")).unwrap();
+        assert!(
+            solution_rs.contains("(21, &crate::day21::Day21),\n    (22, &crate::day22::Day22),\n")
+        );
+        let module = fs::read_to_string(src.join("day22.rs")).unwrap();
+        assert!(module.contains("impl Solution for Day22"));
+        assert!(matches!(
+            scaffold(&root, 22),
+            Err(ScaffoldError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            scaffold(&root, 26),
+            Err(ScaffoldError::InvalidDay(26))
+        ));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
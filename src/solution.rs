@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use crate::answer::Answer;
+use crate::error::{Hint, Message, ParseError};
+
+/// A day's puzzle. The input is parsed once, both parts are then answered from the parsed form.
+pub trait Solution {
+    type Parsed;
+    /// Why the input could not be parsed, see [`ParseError`].
+    type ErrorKind: Display + Hint;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError<Self::ErrorKind>>;
+    fn part1(parsed: &Self::Parsed) -> Answer;
+    fn part2(parsed: &Self::Parsed) -> Answer;
+
+    /// Part 1 with some of the puzzle's constants overridden, without any this is the same as [`Solution::part1`].
+    fn part1_with(parsed: &Self::Parsed, _params: &Params) -> Answer {
+        Self::part1(parsed)
+    }
+
+    /// Part 2 with some of the puzzle's constants overridden, without any this is the same as [`Solution::part2`].
+    fn part2_with(parsed: &Self::Parsed, _params: &Params) -> Answer {
+        Self::part2(parsed)
+    }
+}
+
+/// Overrides for the constants of a puzzle (like a number of steps) by name, examples tend to use smaller ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Params(pub BTreeMap<String, i64>);
+
+impl Params {
+    pub fn new() -> Params {
+        Params::default()
+    }
+
+    pub fn with(mut self, name: &str, value: i64) -> Params {
+        self.0.insert(name.to_string(), value);
+        return self;
+    }
+
+    /// The value of `name`, `default` when it isn't overridden.
+    pub fn get<T: TryFrom<i64>>(&self, name: &str, default: T) -> T {
+        return match self.0.get(name) {
+            Some(&x) => T::try_from(x)
+                .unwrap_or_else(|_| panic!("parameter '{}' is out of range: {}", name, x)),
+            None => default,
+        };
+    }
+}
+
+/// Object safe view of a [`Solution`], so all days can be kept in a single [`REGISTRY`].
+pub trait Solver: Sync {
+    fn prepare(&self, input: &str) -> Result<Box<dyn Prepared>, ParseError<Message>>;
+}
+
+/// The parsed input of a day, ready to answer either part.
+pub trait Prepared {
+    fn part1(&self) -> Answer;
+    fn part2(&self) -> Answer;
+    fn part1_with(&self, params: &Params) -> Answer;
+    fn part2_with(&self, params: &Params) -> Answer;
+
+    fn part(&self, part: u8) -> Option<Answer> {
+        match part {
+            1 => Some(self.part1()),
+            2 => Some(self.part2()),
+            _ => None,
+        }
+    }
+
+    fn part_with(&self, part: u8, params: &Params) -> Option<Answer> {
+        match part {
+            1 => Some(self.part1_with(params)),
+            2 => Some(self.part2_with(params)),
+            _ => None,
+        }
+    }
+}
+
+struct Parsed<S: Solution>(S::Parsed);
+
+impl<S: Solution> Prepared for Parsed<S> {
+    fn part1(&self) -> Answer {
+        S::part1(&self.0)
+    }
+
+    fn part2(&self) -> Answer {
+        S::part2(&self.0)
+    }
+
+    fn part1_with(&self, params: &Params) -> Answer {
+        S::part1_with(&self.0, params)
+    }
+
+    fn part2_with(&self, params: &Params) -> Answer {
+        S::part2_with(&self.0, params)
+    }
+}
+
+impl<S> Solver for S
+where
+    S: Solution + Sync + 'static,
+{
+    fn prepare(&self, input: &str) -> Result<Box<dyn Prepared>, ParseError<Message>> {
+        match S::parse(input) {
+            Ok(parsed) => Ok(Box::new(Parsed::<S>(parsed))),
+            Err(e) => Err(e.map(|kind| Message::of(&kind))),
+        }
+    }
+}
+
+pub const REGISTRY: &[(u8, &dyn Solver)] = &[
+    (1, &crate::day01::Day01),
+    (2, &crate::day02::Day02),
+    (3, &crate::day03::Day03),
+    (4, &crate::day04::Day04),
+    (5, &crate::day05::Day05),
+    (6, &crate::day06::Day06),
+    (7, &crate::day07::Day07),
+    (8, &crate::day08::Day08),
+    (9, &crate::day09::Day09),
+    (10, &crate::day10::Day10),
+    (11, &crate::day11::Day11),
+    (12, &crate::day12::Day12),
+    (13, &crate::day13::Day13),
+    (14, &crate::day14::Day14),
+    (15, &crate::day15::Day15),
+    (16, &crate::day16::Day16),
+    (17, &crate::day17::Day17),
+    (18, &crate::day18::Day18),
+    (19, &crate::day19::Day19),
+    (20, &crate::day20::Day20),
+    (21, &crate::day21::Day21),
+];
+
+pub fn solver(day: u8) -> Option<&'static dyn Solver> {
+    REGISTRY
+        .iter()
+        .find(|(registered, _)| *registered == day)
+        .map(|(_, solver)| *solver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_ordered() {
+        let days: Vec<u8> = REGISTRY.iter().map(|(day, _)| *day).collect();
+        assert!(days.windows(2).all(|w| w[0] < w[1]), "{:?}", days);
+        assert_eq!(days[..21], (1..=21).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn prepared_parts() {
+        let prepared = solver(1)
+            .expect("day 1")
+            .prepare("1abc2\ntwo3four")
+            .unwrap();
+        assert_eq!(prepared.part1(), 45);
+        assert_eq!(prepared.part(2), Some(Answer::from(36)));
+        assert_eq!(prepared.part(3), None);
+        assert!(solver(0).is_none());
+    }
+
+    #[test]
+    fn params() {
+        let params = Params::new().with("steps", 6);
+        assert_eq!(params.get("steps", 64_usize), 6);
+        assert_eq!(params.get("iterations", 1000), 1000);
+        let garden = "...\n.S.\n...";
+        let prepared = solver(21).unwrap().prepare(garden).unwrap();
+        assert_eq!(prepared.part_with(1, &params), Some(Answer::from(5)));
+        assert_eq!(prepared.part_with(1, &Params::new()), prepared.part(1));
+    }
+}
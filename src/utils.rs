@@ -1,87 +1,196 @@
 use std::env;
+use std::fmt::{self, Display};
 use std::fs;
-use std::ops;
-use std::path::Path;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
 
-pub fn resource(project_relative_path: &str) -> String {
-    let current_dir = match env::current_dir() {
-        Result::Ok(s) => match s.to_str() {
-            Some(val) => Result::Ok(val.to_string()),
-            None => Result::Err(env::VarError::NotPresent),
-        },
-        Result::Err(_) => Result::Err(env::VarError::NotPresent),
-    };
+pub mod bfs;
+pub mod cycle;
+#[cfg(test)]
+pub mod differential;
+mod grid;
+mod memo;
+pub mod parallel;
+pub mod parse;
+pub mod polygon;
+mod range_set;
+pub mod search;
+mod vector;
+pub use grid::{EmptyGrid, Grid, UnevenRow};
+pub use memo::Memo;
+pub use range_set::RangeSet;
+pub use vector::{Aabb, Point2D, Point3D, Vector};
+
+/// Names a directory with the puzzle inputs (`day01.txt`, ...), for inputs kept outside the repo.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
 
-    let cargo_dir = env::var("CARGO_MANIFEST_DIR");
-    let root_dir = cargo_dir
-        .or(current_dir)
-        .expect("cannot resolve root resource directory");
-    let final_path = Path::new(&root_dir).join(&project_relative_path);
-    return fs::read_to_string(final_path)
-        .expect(&format!("Could not read '{project_relative_path}'"));
+#[derive(Debug, Error)]
+pub enum InputError {
+    #[error("could not read '{}': {source}", path.display())]
+    File { path: PathBuf, source: io::Error },
+    #[error("could not read stdin: {0}")]
+    Stdin(io::Error),
 }
 
-#[derive(Hash, Eq, PartialEq, Debug, Copy, Clone, PartialOrd, Ord)]
-pub struct Point2D {
-    pub x: i32,
-    pub y: i32,
+/// The crate root when run through cargo, the current directory otherwise.
+fn root_dir() -> PathBuf {
+    return env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .or_else(|| env::current_dir().ok())
+        .unwrap_or_default();
 }
 
-impl Point2D {
-    pub fn new(x: i32, y: i32) -> Point2D {
-        (x, y).into()
-    }
+/// Where a relative `path` of the crate (a default input, a fixture) is, absolute paths are kept
+/// as they are. Paths given by the user are read as they are instead.
+pub fn resolve<P: AsRef<Path>>(path: P) -> PathBuf {
+    return root_dir().join(path);
+}
 
-    pub fn zero() -> Point2D {
-        (0, 0).into()
-    }
+/// Something [`normalize`] had to change about an input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    ByteOrderMark,
+    /// the number of lines ending with `\r\n`
+    CrLf(usize),
+    /// the number of lines with spaces or tabs at the end
+    TrailingWhitespace(usize),
+    /// the number of blank lines after the last one
+    TrailingBlankLines(usize),
+}
 
-    pub fn manhattan_len(self) -> usize {
-        (self.x.abs() + self.y.abs()) as usize
+impl Display for Normalization {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Normalization::ByteOrderMark => write!(f, "removed the byte order mark"),
+            Normalization::CrLf(n) => write!(f, "converted {} CRLF line ending(s)", n),
+            Normalization::TrailingWhitespace(n) => {
+                write!(f, "removed trailing whitespace from {} line(s)", n)
+            }
+            Normalization::TrailingBlankLines(n) => {
+                write!(f, "removed {} blank line(s) at the end", n)
+            }
+        }
     }
+}
 
-    pub fn manhattan_normalize(self) -> (usize, Point2D) {
-        let m_len = self.manhattan_len();
-        let len = m_len as i32;
-        (m_len, (self.x / len, self.y / len).into())
-    }
+/// Gives every input the same line semantics: no BOM, `\n` line endings, no whitespace at the
+/// end of a line, and a single `\n` after the last line (the only change that isn't reported,
+/// editors disagree too much about it). Blank lines at the start are kept for the line numbers.
+pub fn normalize(input: &str) -> (String, Vec<Normalization>) {
+    let mut changes = vec![];
+    let input = match input.strip_prefix('\u{feff}') {
+        Some(x) => {
+            changes.push(Normalization::ByteOrderMark);
+            x
+        }
+        None => input,
+    };
 
-    pub fn clockwise(self) -> Point2D {
-        (self.y * -1, self.x).into()
+    let mut crlf = 0;
+    // the line without the whitespace at the end, and whether there was any
+    let mut lines = vec![];
+    for line in input.split('\n') {
+        let line = match line.strip_suffix('\r') {
+            Some(x) => {
+                crlf += 1;
+                x
+            }
+            None => line,
+        };
+        let trimmed = line.trim_end_matches([' ', '\t']);
+        lines.push((trimmed, trimmed.len() != line.len()));
     }
+    // the empty piece after the final newline (or of an empty input) is not a line
+    if lines.last() == Some(&("", false)) {
+        lines.pop();
+    }
+    let blank = lines
+        .iter()
+        .rev()
+        .take_while(|(line, _)| line.is_empty())
+        .count();
+    lines.truncate(lines.len() - blank);
 
-    pub fn counter_clockwise(self) -> Point2D {
-        (self.y, self.x * -1).into()
+    if crlf > 0 {
+        changes.push(Normalization::CrLf(crlf));
+    }
+    // blank lines at the end are reported as such
+    let whitespace = lines.iter().filter(|(_, trimmed)| *trimmed).count();
+    if whitespace > 0 {
+        changes.push(Normalization::TrailingWhitespace(whitespace));
+    }
+    if blank > 0 {
+        changes.push(Normalization::TrailingBlankLines(blank));
     }
 
-    pub fn translate(self, direction: Direction) -> Point2D {
-        self + match direction {
-            Direction::Up => Point2D::new(0, -1),
-            Direction::Right => Point2D::new(1, 0),
-            Direction::Down => Point2D::new(0, 1),
-            Direction::Left => Point2D::new(-1, 0),
-        }
+    let mut text = lines
+        .iter()
+        .map(|(line, _)| *line)
+        .collect::<Vec<&str>>()
+        .join("\n");
+    if !text.is_empty() {
+        text.push('\n');
     }
+    return (text, changes);
 }
 
-impl From<(i32, i32)> for Point2D {
-    fn from(e: (i32, i32)) -> Point2D {
-        return Point2D { x: e.0, y: e.1 };
+/// Reads the input at `path` (relative to the current directory), `-` reads stdin instead. The
+/// input is [`normalize`]d, the changes are returned along with it.
+pub fn load_input<P: AsRef<Path>>(path: P) -> Result<(String, Vec<Normalization>), InputError> {
+    let path = path.as_ref();
+    if path == Path::new("-") {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .map_err(InputError::Stdin)?;
+        return Ok(normalize(&input));
     }
+    let path = path.to_path_buf();
+    let input = fs::read_to_string(&path).map_err(|source| InputError::File { path, source })?;
+    return Ok(normalize(&input));
 }
 
-impl_op_ex!(+ |a: &Point2D, b: &Point2D| -> Point2D {
-    Point2D { x: a.x + b.x, y: a.y + b.y }
-});
+/// Like [`load_input`], without the changes.
+pub fn read_input<P: AsRef<Path>>(path: P) -> Result<String, InputError> {
+    return load_input(path).map(|(input, _)| input);
+}
 
-impl_op_ex!(*|a: &Point2D, b: &i32| -> Point2D {
-    Point2D {
-        x: a.x * b,
-        y: a.y * b,
-    }
-});
+/// The input of a `day`, from the [`INPUT_DIR_VAR`] directory when it is set, from `src/` otherwise.
+pub fn day_input_path(day: u8) -> PathBuf {
+    let file_name = format!("day{:02}.txt", day);
+    return match env::var_os(INPUT_DIR_VAR) {
+        Some(dir) => Path::new(&dir).join(file_name),
+        None => resolve(Path::new("src").join(file_name)),
+    };
+}
+
+/// Reads a file relative to the crate root (or the current directory when not run through cargo).
+pub fn resource(project_relative_path: &str) -> String {
+    return read_input(resolve(project_relative_path)).unwrap_or_else(|e| panic!("{}", e));
+}
 
-#[derive(Hash, Clone, Copy, PartialEq, Eq)]
+/// The lines of `input` with their 1-based line number, blank lines at the start and the end are skipped.
+pub fn numbered_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    let lines: Vec<&str> = input.split('\n').collect();
+    let is_blank = |line: &&str| line.trim().is_empty();
+    let first = lines
+        .iter()
+        .position(|l| !is_blank(l))
+        .unwrap_or(lines.len());
+    let last = lines
+        .iter()
+        .rposition(|l| !is_blank(l))
+        .map_or(first, |x| x + 1);
+    return lines
+        .into_iter()
+        .enumerate()
+        .take(last)
+        .skip(first)
+        .map(|(i, line)| (i + 1, line));
+}
+
+#[derive(Hash, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Direction {
     Up,
     Right,
@@ -98,9 +207,55 @@ impl Direction {
     ];
 }
 
+/// Like `%`, but the result has the sign of the `modulo`, e.g. `-1 mod 5 = 4`.
 pub fn positive_modulo<T>(value: T, modulo: T) -> T
 where
     T: std::ops::Rem<Output = T> + std::ops::Add<Output = T> + std::marker::Copy,
 {
     ((value % modulo) + modulo) % modulo
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resource_resolves_paths() {
+        let relative = resource("src/day06.txt");
+        let absolute = read_input(resolve("src/day06.txt")).unwrap();
+        assert!(relative.starts_with("Time:"));
+        assert_eq!(relative, absolute);
+    }
+
+    #[test]
+    fn normalize_line_endings() {
+        let (text, changes) = normalize("\u{feff}1abc2\r\npqr3stu8vwx \r\n\r\n  \n");
+        assert_eq!(text, "1abc2\npqr3stu8vwx\n");
+        assert_eq!(
+            changes,
+            vec![
+                Normalization::ByteOrderMark,
+                Normalization::CrLf(3),
+                Normalization::TrailingWhitespace(1),
+                Normalization::TrailingBlankLines(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn normalize_final_newline() {
+        assert_eq!(normalize("a\n\nb"), ("a\n\nb\n".to_string(), vec![]));
+        assert_eq!(normalize("\na\n"), ("\na\n".to_string(), vec![]));
+        assert_eq!(normalize(""), ("".to_string(), vec![]));
+    }
+
+    #[test]
+    fn read_input_reports_missing_files() {
+        let error = read_input("src/day99.txt").unwrap_err();
+        assert!(matches!(error, InputError::File { .. }));
+        // the path is used as given, not joined onto the crate root
+        assert!(error
+            .to_string()
+            .starts_with("could not read 'src/day99.txt'"));
+    }
+}
@@ -0,0 +1,136 @@
+//! Breadth first search one layer (step) at a time, for walks where the step count matters.
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Expands a frontier of states step by step, remembering the distance of everything it reached.
+pub struct Bfs<S, N> {
+    neighbours: N,
+    step: usize,
+    frontier: Vec<S>,
+    distances: HashMap<S, usize>,
+    // how many states were reached at an even and at an odd distance
+    parity_counts: [usize; 2],
+}
+
+impl<S, N, I> Bfs<S, N>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    pub fn new(starts: impl IntoIterator<Item = S>, neighbours: N) -> Bfs<S, N> {
+        let mut bfs = Bfs {
+            neighbours,
+            step: 0,
+            frontier: vec![],
+            distances: HashMap::new(),
+            parity_counts: [0, 0],
+        };
+        for start in starts {
+            if bfs.distances.insert(start.clone(), 0).is_none() {
+                bfs.frontier.push(start);
+                bfs.parity_counts[0] += 1;
+            }
+        }
+        return bfs;
+    }
+
+    /// Steps taken so far.
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// The states first reached in the last step.
+    pub fn frontier(&self) -> &[S] {
+        &self.frontier
+    }
+
+    /// The number of steps to get to `state`, if it has been reached yet.
+    pub fn distance(&self, state: &S) -> Option<usize> {
+        self.distances.get(state).copied()
+    }
+
+    pub fn distances(&self) -> &HashMap<S, usize> {
+        &self.distances
+    }
+
+    /// How many states can be reached in exactly `steps`, when every step can be walked back
+    /// (i.e. the states with a distance of the same parity). Only known for the current step and
+    /// the one before it.
+    pub fn reachable_in(&self, steps: usize) -> usize {
+        assert!(
+            steps <= self.step && steps + 1 >= self.step,
+            "only the last two steps are known"
+        );
+        self.parity_counts[steps % 2]
+    }
+
+    /// Takes one step from every state of the frontier, the new frontier is what wasn't reached before.
+    pub fn advance(&mut self) {
+        self.step += 1;
+        let mut next = vec![];
+        for current in self.frontier.drain(..) {
+            for neighbour in (self.neighbours)(&current) {
+                if !self.distances.contains_key(&neighbour) {
+                    self.distances.insert(neighbour.clone(), self.step);
+                    next.push(neighbour);
+                }
+            }
+        }
+        self.parity_counts[self.step % 2] += next.len();
+        self.frontier = next;
+    }
+
+    /// Advances until `steps` were taken, calling `after_step` after every step. Stops early with
+    /// the result of `after_step` once it returns one.
+    pub fn run<R, F>(&mut self, steps: usize, mut after_step: F) -> Option<R>
+    where
+        F: FnMut(&Self) -> Option<R>,
+    {
+        while self.step < steps {
+            self.advance();
+            if let Some(result) = after_step(self) {
+                return Some(result);
+            }
+        }
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a 2x2 square a-b-d-c-a
+    fn square(node: &char) -> Vec<char> {
+        match node {
+            'a' => vec!['b', 'c'],
+            'b' => vec!['a', 'd'],
+            'c' => vec!['a', 'd'],
+            _ => vec!['b', 'c'],
+        }
+    }
+
+    #[test]
+    fn layers() {
+        let mut bfs = Bfs::new(['a'], square);
+        assert_eq!(bfs.reachable_in(0), 1);
+        bfs.advance();
+        assert_eq!(bfs.frontier(), &['b', 'c']);
+        assert_eq!((bfs.reachable_in(0), bfs.reachable_in(1)), (1, 2));
+        bfs.advance();
+        assert_eq!(bfs.frontier(), &['d']);
+        assert_eq!(bfs.reachable_in(2), 2);
+        assert_eq!(bfs.distance(&'d'), Some(2));
+        assert_eq!(bfs.distance(&'x'), None);
+    }
+
+    #[test]
+    fn run_stops_early() {
+        let mut bfs = Bfs::new([0], |&n: &i32| [n - 1, n + 1]);
+        let result = bfs.run(100, |bfs| (bfs.frontier().len() == 2).then_some(bfs.step()));
+        assert_eq!(result, Some(1));
+        assert_eq!(bfs.run(10, |_| None::<()>), None);
+        assert_eq!((bfs.step(), bfs.reachable_in(10)), (10, 11));
+    }
+}
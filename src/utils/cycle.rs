@@ -0,0 +1,176 @@
+//! Cycle detection for simulations that end up repeating themselves.
+use std::collections::hash_map::{Entry, HashMap};
+use std::hash::Hash;
+
+/// After `prefix` steps, the states repeat every `period` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub prefix: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The first step with the same state as step `n`.
+    pub fn equivalent(&self, n: usize) -> usize {
+        if n < self.prefix {
+            return n;
+        }
+        return self.prefix + (n - self.prefix) % self.period;
+    }
+}
+
+/// The states of a simulation, `states[n]` is the state after `n` steps. Once a state repeats
+/// the simulation stops, the states from there on are known through the `cycle`.
+#[derive(Debug, Clone)]
+pub struct History<S> {
+    pub states: Vec<S>,
+    pub cycle: Option<Cycle>,
+}
+
+impl<S> History<S> {
+    /// The index into `states` of the state after `n` steps, none if it wasn't simulated.
+    pub fn equivalent(&self, n: usize) -> Option<usize> {
+        return match self.cycle {
+            Some(cycle) => Some(cycle.equivalent(n)),
+            None if n < self.states.len() => Some(n),
+            None => None,
+        };
+    }
+
+    /// The state after `n` steps, none if it wasn't simulated.
+    pub fn nth(&self, n: usize) -> Option<&S> {
+        return self.equivalent(n).map(|i| &self.states[i]);
+    }
+
+    /// How many times each of `states` comes up in the first `steps` steps (i.e. from the state
+    /// after 0 steps to the one after `steps`), e.g. to add up something every state contributes.
+    pub fn visits(&self, steps: usize) -> Vec<usize> {
+        return (0..self.states.len())
+            .map(|i| match self.cycle {
+                _ if i > steps => 0,
+                Some(cycle) if i >= cycle.prefix => 1 + (steps - i) / cycle.period,
+                _ => 1,
+            })
+            .collect();
+    }
+}
+
+/// Runs `step` from `start` until `steps` were taken or until a state repeats. States are
+/// told apart by their `fingerprint`, which can be cheaper to keep around than the whole state.
+pub fn simulate_by<S, K, F, G>(
+    start: S,
+    mut step: F,
+    mut fingerprint: G,
+    steps: usize,
+) -> History<S>
+where
+    K: Eq + Hash,
+    F: FnMut(&S) -> S,
+    G: FnMut(&S) -> K,
+{
+    let mut seen = HashMap::<K, usize>::new();
+    let mut states = vec![];
+    let mut state = start;
+    loop {
+        let n = states.len();
+        match seen.entry(fingerprint(&state)) {
+            Entry::Occupied(entry) => {
+                let first = *entry.get();
+                let cycle = Cycle {
+                    prefix: first,
+                    period: n - first,
+                };
+                return History {
+                    states,
+                    cycle: Some(cycle),
+                };
+            }
+            Entry::Vacant(_) if n == steps => {
+                states.push(state);
+                return History {
+                    states,
+                    cycle: None,
+                };
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(n);
+            }
+        }
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+/// [`simulate_by`] for states that can be hashed themselves.
+pub fn simulate<S, F>(start: S, step: F, steps: usize) -> History<S>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    return simulate_by(start, step, S::clone, steps);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 1 2 3 4 2 3 4 ...
+    fn step(n: &u32) -> u32 {
+        if *n == 4 {
+            2
+        } else {
+            n + 1
+        }
+    }
+
+    #[test]
+    fn finds_the_cycle() {
+        let history = simulate(0, step, 1_000_000);
+        let cycle = Cycle {
+            prefix: 2,
+            period: 3,
+        };
+        assert_eq!(history.cycle, Some(cycle));
+        assert_eq!(history.states, vec![0, 1, 2, 3, 4]);
+        assert_eq!(cycle.equivalent(1), 1);
+        assert_eq!(cycle.equivalent(5), 2);
+        assert_eq!(history.nth(1_000_000), Some(&4)); // 2 + 999_998 % 3
+    }
+
+    #[test]
+    fn stops_after_steps() {
+        let history = simulate(0, step, 3);
+        assert_eq!(history.cycle, None);
+        assert_eq!(history.nth(3), Some(&3));
+        assert_eq!(history.nth(4), None);
+        assert_eq!(history.visits(2), vec![1, 1, 1, 0]);
+    }
+
+    #[test]
+    fn visits() {
+        // 0 1 2 3 4 2 3 4 2
+        let history = simulate(0, step, 100);
+        assert_eq!(history.visits(8), vec![1, 1, 3, 2, 2]);
+        assert_eq!(history.visits(2), vec![1, 1, 1, 0, 0]);
+        let by_parity = simulate_by(0, step, |n| n % 2, 100);
+        assert_eq!(by_parity.states, vec![0, 1]);
+    }
+
+    #[test]
+    fn fingerprints_each_state_once() {
+        let mut fingerprints = 0;
+        let history = simulate_by(
+            0,
+            step,
+            |&n| {
+                fingerprints += 1;
+                n
+            },
+            100,
+        );
+        // the 5 states and the repeated one
+        assert_eq!(history.states.len(), 5);
+        assert_eq!(fingerprints, 6);
+    }
+}
@@ -0,0 +1,87 @@
+//! Cross-checking the optimized solvers against brute-force references on generated inputs.
+//! The shortcuts some days take (cycles, extrapolation, range splitting) only hold for inputs
+//! shaped like the puzzle's, the generators are expected to produce those, just small enough
+//! for the brute force to finish.
+use std::fmt::Debug;
+
+/// A xorshift generator, every run generates the same cases.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        // the state must not be 0, the multiplier spreads small seeds over all the bits
+        Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        return x;
+    }
+
+    /// A number in `range`, which must not be empty.
+    pub fn range(&mut self, range: std::ops::Range<i64>) -> i64 {
+        let len = (range.end - range.start) as u64;
+        return range.start + (self.next_u64() % len) as i64;
+    }
+
+    /// An index below `n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        return (self.next_u64() % n as u64) as usize;
+    }
+
+    /// True with the probability `percent` / 100.
+    pub fn chance(&mut self, percent: u64) -> bool {
+        return self.next_u64() % 100 < percent;
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        return &items[self.below(items.len())];
+    }
+}
+
+/// Generates `cases` inputs and compares the answers of `optimized` and `reference` on each,
+/// panics with the first input they disagree on.
+pub fn cross_check<I, A, G, O, R>(cases: u64, mut generate: G, optimized: O, reference: R)
+where
+    I: Debug,
+    A: PartialEq + Debug,
+    G: FnMut(&mut Rng) -> I,
+    O: Fn(&I) -> A,
+    R: Fn(&I) -> A,
+{
+    for case in 0..cases {
+        let input = generate(&mut Rng::new(case));
+        let expected = reference(&input);
+        let actual = optimized(&input);
+        assert_eq!(
+            actual, expected,
+            "case {} differs from the reference, input: {:#?}",
+            case, input
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rng_is_deterministic() {
+        let a: Vec<u64> = (0..5).map(|_| Rng::new(7).next_u64()).collect();
+        assert!(a.iter().all(|&x| x == a[0]));
+        let mut rng = Rng::new(7);
+        assert!((0..1000)
+            .map(|_| rng.range(-3..4))
+            .all(|x| (-3..4).contains(&x)));
+    }
+
+    #[test]
+    #[should_panic(expected = "case 0 differs")]
+    fn cross_check_reports_differences() {
+        cross_check(10, |rng| rng.range(0..10), |&x| x, |&x| x + 1);
+    }
+}
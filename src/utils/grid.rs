@@ -0,0 +1,334 @@
+use super::{numbered_lines, Direction, Point2D};
+use crate::error::ParseError;
+use std::ops::{Index, IndexMut};
+use thiserror::Error;
+
+/// A row of a parsed grid is longer or shorter than the first one.
+#[derive(Debug, Error, PartialEq, Eq, Clone, Copy)]
+#[error("expected a row of {expected} tiles, got {found}")]
+pub struct UnevenRow {
+    pub expected: usize,
+    pub found: usize,
+}
+
+/// There are no rows to parse a grid from.
+#[derive(Debug, Error, PartialEq, Eq, Clone, Copy)]
+#[error("expected at least one row of tiles")]
+pub struct EmptyGrid;
+
+/// A rectangular grid stored row by row in one `Vec`, `(0, 0)` is the top left corner.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A `width` x `height` grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        return Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        };
+    }
+
+    /// Panics when the rows don't have the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Grid<T> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+        assert!(
+            rows.iter().all(|row| row.len() == width),
+            "rows of a grid must have the same length"
+        );
+        let cells = rows.into_iter().flatten().collect();
+        return Grid {
+            width,
+            height,
+            cells,
+        };
+    }
+
+    /// Parses the non-blank lines of `input`, one cell per char. `tile` maps the chars to cells,
+    /// its errors (and uneven rows) are reported at the offending char. There has to be a row.
+    pub fn parse<K, F>(input: &str, tile: F) -> Result<Grid<T>, ParseError<K>>
+    where
+        K: From<UnevenRow> + From<EmptyGrid>,
+        F: FnMut(char) -> Result<T, K>,
+    {
+        return Self::parse_marked(input, None, tile).map(|(grid, _)| grid);
+    }
+
+    /// Like [`Grid::parse`], but also returns where the `marker` chars are (e.g. the start `S`).
+    /// `tile` still gets the markers, to tell what is underneath them.
+    pub fn parse_marked<K, F>(
+        input: &str,
+        marker: Option<char>,
+        mut tile: F,
+    ) -> Result<(Grid<T>, Vec<Point2D>), ParseError<K>>
+    where
+        K: From<UnevenRow> + From<EmptyGrid>,
+        F: FnMut(char) -> Result<T, K>,
+    {
+        let mut cells = vec![];
+        let mut marked = vec![];
+        let mut width = None;
+        let mut height = 0;
+        for (y, (line_no, line)) in numbered_lines(input).enumerate() {
+            let mut found = 0;
+            for (x, ch) in line.chars().enumerate() {
+                if width.is_some_and(|expected| x == expected) {
+                    let kind = UnevenRow {
+                        expected: x,
+                        found: line.chars().count(),
+                    };
+                    return Err(ParseError::new(line_no, x + 1, kind.into()));
+                }
+                if marker == Some(ch) {
+                    marked.push(Point2D::new(x as i32, y as i32));
+                }
+                cells.push(tile(ch).map_err(|kind| ParseError::new(line_no, x + 1, kind))?);
+                found += 1;
+            }
+            match width {
+                Some(expected) if found < expected => {
+                    let kind = UnevenRow { expected, found };
+                    return Err(ParseError::new(line_no, found + 1, kind.into()));
+                }
+                Some(_) => {}
+                None => width = Some(found),
+            }
+            height += 1;
+        }
+        if height == 0 {
+            return Err(ParseError::new(1, 1, EmptyGrid.into()));
+        }
+        let grid = Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        };
+        return Ok((grid, marked));
+    }
+
+    /// The grid as text, one line per row, the inverse of [`Grid::parse`].
+    pub fn render<F>(&self, tile: F) -> String
+    where
+        F: Fn(&T) -> char,
+    {
+        let mut text = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            text.extend(row.iter().map(&tile));
+            text.push('\n');
+        }
+        return text;
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The point just past the bottom right corner, i.e. `(width, height)`.
+    pub fn bounds(&self) -> Point2D {
+        Point2D::new(self.width as i32, self.height as i32)
+    }
+
+    pub fn contains(&self, point: Point2D) -> bool {
+        return point.x >= 0
+            && point.y >= 0
+            && (point.x as usize) < self.width
+            && (point.y as usize) < self.height;
+    }
+
+    fn offset(&self, point: Point2D) -> Option<usize> {
+        if !self.contains(point) {
+            return None;
+        }
+        return Some(point.y as usize * self.width + point.x as usize);
+    }
+
+    /// The cell at `point`, none if it is out of bounds.
+    pub fn get(&self, point: Point2D) -> Option<&T> {
+        return self.offset(point).map(|i| &self.cells[i]);
+    }
+
+    pub fn get_mut(&mut self, point: Point2D) -> Option<&mut T> {
+        return self.offset(point).map(|i| &mut self.cells[i]);
+    }
+
+    /// The neighbours of `point` that are within bounds, in [`Direction::ALL`] order.
+    pub fn neighbours(&self, point: Point2D) -> impl Iterator<Item = (Direction, Point2D, &T)> {
+        return Direction::ALL.iter().filter_map(move |&direction| {
+            let next = point.translate(direction);
+            self.get(next).map(|cell| (direction, next, cell))
+        });
+    }
+
+    /// All cells with their position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point2D, &T)> {
+        let width = self.width;
+        return self.cells.iter().enumerate().map(move |(i, cell)| {
+            let point = Point2D::new((i % width) as i32, (i / width) as i32);
+            (point, cell)
+        });
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on 0, an empty grid has no cells anyway
+        return self.cells.chunks(self.width.max(1));
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        return &self.cells[y * self.width..(y + 1) * self.width];
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} out of bounds", x);
+        return self.cells.iter().skip(x).step_by(self.width);
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        return (0..self.width).map(|x| self.column(x));
+    }
+
+    /// The grid turned a quarter to the right, the first column becomes the first row (reversed).
+    pub fn rotate_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let cells = (0..self.width)
+            .flat_map(|x| (0..self.height).rev().map(move |y| (x, y)))
+            .map(|(x, y)| self.cells[y * self.width + x].clone())
+            .collect();
+        return Grid {
+            width: self.height,
+            height: self.width,
+            cells,
+        };
+    }
+}
+
+impl<T> Index<Point2D> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point2D) -> &T {
+        return self
+            .get(point)
+            .unwrap_or_else(|| panic!("{:?} is out of bounds", point));
+    }
+}
+
+impl<T> IndexMut<Point2D> for Grid<T> {
+    fn index_mut(&mut self, point: Point2D) -> &mut T {
+        return self
+            .get_mut(point)
+            .unwrap_or_else(|| panic!("{:?} is out of bounds", point));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<u8> {
+        Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]])
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Kind {
+        NotADigit,
+        Uneven(UnevenRow),
+        Empty,
+    }
+
+    impl From<EmptyGrid> for Kind {
+        fn from(_: EmptyGrid) -> Kind {
+            Kind::Empty
+        }
+    }
+
+    impl From<UnevenRow> for Kind {
+        fn from(e: UnevenRow) -> Kind {
+            Kind::Uneven(e)
+        }
+    }
+
+    fn digit(ch: char) -> Result<u8, Kind> {
+        return ch.to_digit(10).map(|d| d as u8).ok_or(Kind::NotADigit);
+    }
+
+    #[test]
+    fn parse_and_render() {
+        let grid = Grid::parse("\n123\n456\n", digit).unwrap();
+        assert_eq!(grid, example());
+        assert_eq!(grid.render(|d| (b'0' + d) as char), "123\n456\n");
+        let (grid, marked) = Grid::parse_marked("S.\n.S", Some('S'), Ok::<_, Kind>).unwrap();
+        assert_eq!(marked, vec![Point2D::new(0, 0), Point2D::new(1, 1)]);
+        assert_eq!(grid.render(|&ch| ch), "S.\n.S\n");
+    }
+
+    #[test]
+    fn parse_error() {
+        let result = Grid::parse("123\n4x6", digit);
+        assert_eq!(result.err(), Some(ParseError::new(2, 2, Kind::NotADigit)));
+        let uneven = |expected, found| Kind::Uneven(UnevenRow { expected, found });
+        let result = Grid::parse("123\n4567", digit);
+        assert_eq!(result.err(), Some(ParseError::new(2, 4, uneven(3, 4))));
+        let result = Grid::parse("123\n45", digit);
+        assert_eq!(result.err(), Some(ParseError::new(2, 3, uneven(3, 2))));
+        let result = Grid::parse("\n  \n", digit);
+        assert_eq!(result.err(), Some(ParseError::new(1, 1, Kind::Empty)));
+    }
+
+    #[test]
+    fn get() {
+        let grid = example();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(Point2D::new(2, 1)), Some(&6));
+        assert_eq!(grid.get(Point2D::new(3, 0)), None);
+        assert_eq!(grid.get(Point2D::new(0, -1)), None);
+        assert_eq!(grid[Point2D::new(1, 0)], 2);
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = example();
+        let corner: Vec<_> = grid.neighbours(Point2D::new(0, 0)).collect();
+        assert_eq!(
+            corner,
+            vec![
+                (Direction::Right, Point2D::new(1, 0), &2),
+                (Direction::Down, Point2D::new(0, 1), &4),
+            ]
+        );
+        assert_eq!(grid.neighbours(Point2D::new(1, 1)).count(), 3);
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = example();
+        let rows: Vec<&[u8]> = grid.rows().collect();
+        assert_eq!(rows, vec![&[1, 2, 3], &[4, 5, 6]]);
+        let columns: Vec<Vec<u8>> = grid.columns().map(|c| c.copied().collect()).collect();
+        assert_eq!(columns, vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+    }
+
+    #[test]
+    fn rotate_clockwise() {
+        let rotated = example().rotate_clockwise();
+        assert_eq!(
+            rotated,
+            Grid::from_rows(vec![vec![4, 1], vec![5, 2], vec![6, 3]])
+        );
+        let full_circle = (0..4).fold(example(), |grid, _| grid.rotate_clockwise());
+        assert_eq!(full_circle, example());
+    }
+}
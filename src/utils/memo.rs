@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A cache for recursive functions. Recursive calls go through [`Memo::get_or`], which hands the
+/// memo back to the computation, e.g.
+///
+/// ```
+/// use aoc_2023::utils::Memo;
+///
+/// fn fibonacci(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+///     memo.get_or(n, |memo| match n {
+///         0 | 1 => n,
+///         _ => fibonacci(n - 1, memo) + fibonacci(n - 2, memo),
+///     })
+/// }
+///
+/// assert_eq!(fibonacci(90, &mut Memo::new()), 2880067194370816120);
+/// ```
+///
+/// Keys are best kept cheap: indices into the input (e.g. how long the remaining suffix is) or
+/// slices borrowed from it, rather than strings built on every call.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Memo<K, V> {
+        Memo {
+            cache: HashMap::new(),
+        }
+    }
+
+    /// The cached value for `key`, computed by `compute` the first time.
+    pub fn get_or<F>(&mut self, key: K, compute: F) -> V
+    where
+        F: FnOnce(&mut Memo<K, V>) -> V,
+    {
+        if let Some(value) = self.cache.get(&key) {
+            return value.clone();
+        }
+        let value = compute(self);
+        self.cache.insert(key, value.clone());
+        return value;
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    /// How many values are cached.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Default for Memo<K, V> {
+    fn default() -> Memo<K, V> {
+        Memo::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the number of ways to climb `steps` stairs taking 1 or 2 at a time, counting the calls
+    fn climb(steps: usize, memo: &mut Memo<usize, u64>, calls: &mut usize) -> u64 {
+        memo.get_or(steps, |memo| {
+            *calls += 1;
+            match steps {
+                0 | 1 => 1,
+                _ => climb(steps - 1, memo, calls) + climb(steps - 2, memo, calls),
+            }
+        })
+    }
+
+    #[test]
+    fn computes_once() {
+        let mut memo = Memo::new();
+        let mut calls = 0;
+        assert_eq!(climb(50, &mut memo, &mut calls), 20365011074);
+        assert_eq!(calls, 51);
+        assert_eq!(memo.len(), 51);
+        assert_eq!(memo.get(&3), Some(&3));
+    }
+
+    #[test]
+    fn borrowed_keys() {
+        let words = ["a", "b", "a"];
+        let mut memo = Memo::<&[&str], usize>::new();
+        for suffix in 0..words.len() {
+            memo.get_or(&words[suffix..], |_| suffix);
+        }
+        assert_eq!(memo.get(&&words[2..]), Some(&2));
+    }
+}
@@ -0,0 +1,29 @@
+//! Independent work items, run on a thread pool with the `parallel` feature and one after
+//! the other without it. The results are the same either way, in the order of the items.
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// `f` applied to each of the `items`, the results are in the order of the items.
+pub fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    return items.par_iter().map(f).collect();
+    #[cfg(not(feature = "parallel"))]
+    return items.iter().map(f).collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_keeps_the_order() {
+        let items: Vec<u64> = (0..1000).collect();
+        let squares = map(&items, |x| x * x);
+        assert_eq!(squares, items.iter().map(|x| x * x).collect::<Vec<u64>>());
+    }
+}
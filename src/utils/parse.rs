@@ -0,0 +1,263 @@
+//! Building blocks for the puzzle input formats. A [`Span`] is a piece of a line that knows
+//! where it is, so anything going wrong with it becomes a [`ParseError`] pointing right at it.
+//! The error kinds are the day's own, the combinators take a function making them.
+use super::numbered_lines;
+use crate::error::ParseError;
+use std::str::FromStr;
+
+/// A piece of the line `line_no` (1-based) of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span<'a> {
+    pub line_no: usize,
+    /// the whole line, for the column of `text`
+    pub line: &'a str,
+    /// a subslice of `line`
+    pub text: &'a str,
+}
+
+/// The blank lines at the start and the end are skipped, like [`numbered_lines`]. Trailing
+/// whitespace (including a `\r`) is not part of the spans.
+pub fn lines(input: &str) -> impl Iterator<Item = Span<'_>> {
+    return numbered_lines(input).map(|(line_no, line)| Span::new(line_no, line).trim_end());
+}
+
+/// The blocks of lines separated by blank lines.
+pub fn sections(input: &str) -> Vec<Vec<Span<'_>>> {
+    let mut sections = vec![];
+    let mut section = vec![];
+    for line in lines(input) {
+        if line.is_empty() {
+            if !section.is_empty() {
+                sections.push(section);
+                section = vec![];
+            }
+        } else {
+            section.push(line);
+        }
+    }
+    if !section.is_empty() {
+        sections.push(section);
+    }
+    return sections;
+}
+
+/// A section with a header line like `seed-to-soil map:`, returns the header without the `:`
+/// and the lines after it.
+pub fn labelled<'s, 'a>(section: &'s [Span<'a>]) -> Option<(Span<'a>, &'s [Span<'a>])> {
+    let (header, lines) = section.split_first()?;
+    return Some((header.strip_suffix(":")?, lines));
+}
+
+impl<'a> Span<'a> {
+    /// All of `line`.
+    pub fn new(line_no: usize, line: &'a str) -> Span<'a> {
+        Span {
+            line_no,
+            line,
+            text: line,
+        }
+    }
+
+    fn sub(&self, text: &'a str) -> Span<'a> {
+        Span { text, ..*self }
+    }
+
+    /// The span without its first `n` bytes.
+    pub fn skip(&self, n: usize) -> Span<'a> {
+        self.sub(&self.text[n.min(self.text.len())..])
+    }
+
+    /// A [`ParseError`] pointing at the start of the span.
+    pub fn error<K>(&self, kind: K) -> ParseError<K> {
+        ParseError::at(self.line_no, self.line, self.text, kind)
+    }
+
+    /// A [`ParseError`] pointing just past the end of the span.
+    pub fn error_after<K>(&self, kind: K) -> ParseError<K> {
+        ParseError::at(self.line_no, self.line, &self.text[self.text.len()..], kind)
+    }
+
+    /// 1-based, in chars.
+    pub fn column(&self) -> usize {
+        self.error(()).column
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn trim(&self) -> Span<'a> {
+        self.sub(self.text.trim())
+    }
+
+    pub fn trim_end(&self) -> Span<'a> {
+        self.sub(self.text.trim_end())
+    }
+
+    pub fn strip_prefix(&self, prefix: &str) -> Option<Span<'a>> {
+        self.text.strip_prefix(prefix).map(|text| self.sub(text))
+    }
+
+    pub fn strip_suffix(&self, suffix: &str) -> Option<Span<'a>> {
+        self.text.strip_suffix(suffix).map(|text| self.sub(text))
+    }
+
+    pub fn split_once(&self, separator: &str) -> Option<(Span<'a>, Span<'a>)> {
+        let (a, b) = self.text.split_once(separator)?;
+        return Some((self.sub(a), self.sub(b)));
+    }
+
+    pub fn split(&self, separator: &'a str) -> impl Iterator<Item = Span<'a>> + 'a {
+        let span = *self;
+        return self.text.split(separator).map(move |text| span.sub(text));
+    }
+
+    /// The pieces between whitespace.
+    pub fn words(&self) -> impl Iterator<Item = Span<'a>> + 'a {
+        let span = *self;
+        return self.text.split_whitespace().map(move |text| span.sub(text));
+    }
+
+    /// The longest runs of `wanted` chars, e.g. the numbers in `467..114..`.
+    pub fn runs<F>(&self, wanted: F) -> impl Iterator<Item = Span<'a>> + 'a
+    where
+        F: Fn(char) -> bool + 'a,
+    {
+        let span = *self;
+        return self
+            .text
+            .split(move |ch: char| !wanted(ch))
+            .filter(|text| !text.is_empty())
+            .map(move |text| span.sub(text));
+    }
+
+    /// Fails at the first char that isn't `allowed`.
+    pub fn check_chars<K, A, F>(&self, allowed: A, kind: F) -> Result<(), ParseError<K>>
+    where
+        A: Fn(char) -> bool,
+        F: FnOnce(char) -> K,
+    {
+        return match self.text.char_indices().find(|&(_, ch)| !allowed(ch)) {
+            Some((i, ch)) => Err(self.sub(&self.text[i..]).error(kind(ch))),
+            None => Ok(()),
+        };
+    }
+
+    /// The whole span as a `T`, `kind` gets the text when it isn't one.
+    pub fn parse<T, K, F>(&self, kind: F) -> Result<T, ParseError<K>>
+    where
+        T: FromStr,
+        F: FnOnce(&str) -> K,
+    {
+        return self.text.parse().map_err(|_| self.error(kind(self.text)));
+    }
+
+    /// A whitespace separated list, e.g. `79 14  55 13`.
+    pub fn numbers<T, K, F>(&self, kind: F) -> Result<Vec<T>, ParseError<K>>
+    where
+        T: FromStr,
+        F: Fn(&str) -> K,
+    {
+        return self.words().map(|word| word.parse(&kind)).collect();
+    }
+
+    /// A list with a `separator`, e.g. `1,1,3`. The items are trimmed.
+    pub fn list<T, K, F>(&self, separator: &'a str, kind: F) -> Result<Vec<T>, ParseError<K>>
+    where
+        T: FromStr,
+        F: Fn(&str) -> K,
+    {
+        return self
+            .split(separator)
+            .map(|item| item.trim().parse(&kind))
+            .collect();
+    }
+
+    /// The values after a `label: ` header, e.g. `Time:      7  15   30`.
+    pub fn header(&self, label: &str) -> Option<Span<'a>> {
+        let values = self.strip_prefix(label)?.strip_prefix(":")?;
+        return Some(values.trim());
+    }
+
+    /// A `key <separator> value` pair, both trimmed, e.g. `x=787` or `Game 1: 3 blue`.
+    pub fn key_value(&self, separator: &str) -> Option<(Span<'a>, Span<'a>)> {
+        let (key, value) = self.split_once(separator)?;
+        return Some((key.trim(), value.trim()));
+    }
+
+    /// A `name = (a, b, ...)` tuple.
+    pub fn tuple(&self) -> Option<(Span<'a>, Vec<Span<'a>>)> {
+        let (name, values) = self.key_value("=")?;
+        let values = values.strip_prefix("(")?.strip_suffix(")")?;
+        return Some((name, values.split(",").map(|v| v.trim()).collect()));
+    }
+
+    /// A `label{inner}` block (with the given brackets), the label can be empty.
+    pub fn block(&self, open: char, close: char) -> Option<(Span<'a>, Span<'a>)> {
+        let (label, inner) = self.text.split_once(open)?;
+        let inner = inner.strip_suffix(close)?;
+        return Some((self.sub(label), self.sub(inner)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Kind {
+        NotANumber(String),
+        Unexpected(char),
+    }
+
+    fn not_a_number(text: &str) -> Kind {
+        Kind::NotANumber(text.to_string())
+    }
+
+    #[test]
+    fn numbers_and_lists() {
+        let line = lines("\nseeds: 79 14  55\n").next().unwrap();
+        let seeds = line.header("seeds").unwrap();
+        assert_eq!(seeds.column(), 8);
+        assert_eq!(seeds.numbers(not_a_number), Ok(vec![79, 14, 55]));
+        let result = Span::new(3, "1, 1,x3").list::<u8, _, _>(",", not_a_number);
+        let expected = ParseError::new(3, 6, Kind::NotANumber("x3".to_string()));
+        assert_eq!(result, Err(expected));
+    }
+
+    #[test]
+    fn sections_and_labels() {
+        let input = "\nseeds: 1\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\n\nsoil-to-fertilizer map:\n0 15 37\n";
+        let sections = sections(input);
+        assert_eq!(sections.len(), 3);
+        let (label, lines) = labelled(&sections[1]).unwrap();
+        assert_eq!((label.text, label.line_no), ("seed-to-soil map", 4));
+        assert_eq!(lines.len(), 2);
+        assert_eq!(sections[2][1].text, "0 15 37");
+        assert_eq!(labelled(&sections[0]), None);
+    }
+
+    #[test]
+    fn tuples_and_blocks() {
+        let (name, values) = Span::new(1, "AAA = (BBB, CCC)").tuple().unwrap();
+        assert_eq!(name.text, "AAA");
+        let values: Vec<(&str, usize)> = values.iter().map(|v| (v.text, v.column())).collect();
+        assert_eq!(values, vec![("BBB", 8), ("CCC", 13)]);
+
+        let (label, rules) = Span::new(1, "px{a<2006:qkq,rfg}").block('{', '}').unwrap();
+        assert_eq!(label.text, "px");
+        let rules: Vec<&str> = rules.split(",").map(|r| r.text).collect();
+        assert_eq!(rules, vec!["a<2006:qkq", "rfg"]);
+        let (key, value) = Span::new(1, "x=787").key_value("=").unwrap();
+        assert_eq!((key.text, value.text), ("x", "787"));
+    }
+
+    #[test]
+    fn errors() {
+        let span = Span::new(2, "??.#x?");
+        let result = span.check_chars(|ch| ".#?".contains(ch), Kind::Unexpected);
+        assert_eq!(result, Err(ParseError::new(2, 5, Kind::Unexpected('x'))));
+        let (springs, _) = Span::new(2, "??.# 1,1").split_once(" ").unwrap();
+        assert_eq!(springs.error_after(()), ParseError::new(2, 5, ()));
+    }
+}
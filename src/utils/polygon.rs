@@ -0,0 +1,143 @@
+//! Geometry of simple polygons with lattice (integer) vertices, given in order around the
+//! polygon. The last vertex connects back to the first one.
+use super::Point2D;
+use num::{Integer, Signed};
+
+/// Which way the vertices go around, as seen on a grid where `y` grows downwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+    /// no area at all, e.g. all the vertices are on a line
+    Degenerate,
+}
+
+fn edges<T: Copy>(vertices: &[Point2D<T>]) -> impl Iterator<Item = (Point2D<T>, Point2D<T>)> + '_ {
+    return vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b));
+}
+
+/// Twice the signed area (the shoelace formula), which is always an integer. Positive when the
+/// vertices go clockwise.
+pub fn double_signed_area<T: Signed + Copy>(vertices: &[Point2D<T>]) -> T {
+    return edges(vertices).fold(T::zero(), |sum, (a, b)| sum + a.cross(b));
+}
+
+/// The area, rounded down when it's a half.
+pub fn area<T: Integer + Signed + Copy>(vertices: &[Point2D<T>]) -> T {
+    let two = T::one() + T::one();
+    return double_signed_area(vertices).abs() / two;
+}
+
+pub fn orientation<T: Signed + Copy>(vertices: &[Point2D<T>]) -> Orientation {
+    let area = double_signed_area(vertices);
+    return if area.is_positive() {
+        Orientation::Clockwise
+    } else if area.is_negative() {
+        Orientation::CounterClockwise
+    } else {
+        Orientation::Degenerate
+    };
+}
+
+/// How many lattice points are on the edges, which is the perimeter when all edges are
+/// horizontal or vertical.
+pub fn boundary_points<T: Integer + Signed + Copy>(vertices: &[Point2D<T>]) -> T {
+    return edges(vertices).fold(T::zero(), |sum, (a, b)| {
+        let d = b - a;
+        sum + d.x.abs().gcd(&d.y.abs())
+    });
+}
+
+/// How many lattice points are strictly inside, by Pick's theorem: `A = I + B/2 - 1`.
+pub fn interior_points<T: Integer + Signed + Copy>(vertices: &[Point2D<T>]) -> T {
+    let two = T::one() + T::one();
+    let double_area = double_signed_area(vertices).abs();
+    return (double_area - boundary_points(vertices) + two) / two;
+}
+
+pub fn on_boundary<T: Signed + Copy + Ord>(vertices: &[Point2D<T>], point: Point2D<T>) -> bool {
+    return edges(vertices).any(|(a, b)| {
+        (b - a).cross(point - a).is_zero()
+            && a.x.min(b.x) <= point.x
+            && point.x <= a.x.max(b.x)
+            && a.y.min(b.y) <= point.y
+            && point.y <= a.y.max(b.y)
+    });
+}
+
+/// Whether `point` is strictly inside, by counting the edges a ray towards `+x` crosses.
+pub fn contains<T: Signed + Copy + Ord>(vertices: &[Point2D<T>], point: Point2D<T>) -> bool {
+    if on_boundary(vertices, point) {
+        return false;
+    }
+    let crossings = edges(vertices)
+        .filter(|&(a, b)| (a.y > point.y) != (b.y > point.y))
+        .filter(|&(a, b)| {
+            // which side of the edge the point is on tells if the ray hits it
+            let side = (b - a).cross(point - a);
+            if b.y > a.y {
+                side.is_positive()
+            } else {
+                side.is_negative()
+            }
+        })
+        .count();
+    return crossings % 2 == 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a 5x4 rectangle with a 2x2 notch cut out of the top right, an L with an area of 16
+    //   ####.
+    //   #..#.
+    //   #..###
+    //   #....#
+    //   ######
+    fn notched() -> Vec<Point2D> {
+        [(0, 0), (3, 0), (3, 2), (5, 2), (5, 4), (0, 4)]
+            .map(Point2D::from)
+            .to_vec()
+    }
+
+    #[test]
+    fn area_and_points() {
+        let polygon = notched();
+        assert_eq!(double_signed_area(&polygon), 32);
+        assert_eq!(area(&polygon), 16);
+        assert_eq!(boundary_points(&polygon), 18);
+        assert_eq!(interior_points(&polygon), 8);
+        let diagonal = [(0, 0), (2, 2), (0, 4)].map(Point2D::from);
+        assert_eq!(boundary_points(&diagonal), 8);
+        assert_eq!(interior_points(&diagonal), 1);
+    }
+
+    #[test]
+    fn orientations() {
+        let mut polygon = notched();
+        assert_eq!(orientation(&polygon), Orientation::Clockwise);
+        polygon.reverse();
+        assert_eq!(orientation(&polygon), Orientation::CounterClockwise);
+        assert_eq!(area(&polygon), 16);
+        let line = [(0, 0), (1, 1), (2, 2)].map(Point2D::from);
+        assert_eq!(orientation(&line), Orientation::Degenerate);
+    }
+
+    #[test]
+    fn point_in_polygon() {
+        let polygon = notched();
+        let inside: Vec<Point2D> = (-1..7)
+            .flat_map(|y| (-1..7).map(move |x| Point2D::new(x, y)))
+            .filter(|&p| contains(&polygon, p))
+            .collect();
+        assert_eq!(inside.len() as i32, interior_points(&polygon));
+        assert!(contains(&polygon, Point2D::new(4, 3)));
+        assert!(!contains(&polygon, Point2D::new(4, 1)));
+        assert!(on_boundary(&polygon, Point2D::new(4, 2)));
+        assert!(!contains(&polygon, Point2D::new(4, 2)));
+    }
+}
@@ -0,0 +1,189 @@
+use num::Zero;
+use std::cmp::{max, min};
+use std::ops::{Add, Range, Sub};
+
+/// A set of values kept as sorted, disjoint half-open ranges. Ranges that touch are merged, so
+/// two sets with the same values are equal.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Copy + Ord> RangeSet<T> {
+    pub fn new() -> RangeSet<T> {
+        RangeSet { ranges: vec![] }
+    }
+
+    /// The disjoint ranges of the set, in order.
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        return self.ranges.iter().any(|r| r.contains(&value));
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        self.ranges.push(range);
+        self.normalize();
+    }
+
+    /// sorts the ranges, drops the empty ones and merges the ones that overlap or touch
+    fn normalize(&mut self) {
+        self.ranges.retain(|r| r.start < r.end);
+        self.ranges.sort_by_key(|r| r.start);
+        let mut merged: Vec<Range<T>> = Vec::with_capacity(self.ranges.len());
+        for range in self.ranges.drain(..) {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = max(last.end, range.end),
+                _ => merged.push(range),
+            }
+        }
+        self.ranges = merged;
+    }
+
+    pub fn union(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        return self.ranges.iter().chain(&other.ranges).cloned().collect();
+    }
+
+    pub fn intersection(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        return self
+            .ranges
+            .iter()
+            .flat_map(|a| {
+                other
+                    .ranges
+                    .iter()
+                    .map(move |b| max(a.start, b.start)..min(a.end, b.end))
+            })
+            .collect();
+    }
+
+    /// The values of `self` that aren't in `other`.
+    pub fn difference(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut result = vec![];
+        for range in &self.ranges {
+            let mut start = range.start;
+            for cut in other.ranges.iter().filter(|b| b.start < range.end) {
+                if cut.end <= start {
+                    continue;
+                }
+                if start < cut.start {
+                    result.push(start..cut.start);
+                }
+                start = max(start, cut.end);
+            }
+            if start < range.end {
+                result.push(start..range.end);
+            }
+        }
+        return RangeSet { ranges: result };
+    }
+
+    /// The values below `threshold` and the ones from `threshold` on.
+    pub fn split_at(&self, threshold: T) -> (RangeSet<T>, RangeSet<T>) {
+        let mut below = RangeSet::new();
+        let mut above = RangeSet::new();
+        for range in &self.ranges {
+            if range.end <= threshold {
+                below.ranges.push(range.clone());
+            } else if threshold <= range.start {
+                above.ranges.push(range.clone());
+            } else {
+                below.ranges.push(range.start..threshold);
+                above.ranges.push(threshold..range.end);
+            }
+        }
+        return (below, above);
+    }
+
+    /// The set with `offset` added to every value.
+    pub fn shift(&self, offset: T) -> RangeSet<T>
+    where
+        T: Add<Output = T>,
+    {
+        let ranges = self.ranges.iter();
+        return RangeSet {
+            ranges: ranges.map(|r| r.start + offset..r.end + offset).collect(),
+        };
+    }
+
+    /// How many values there are in the set.
+    pub fn len(&self) -> T
+    where
+        T: Zero + Sub<Output = T>,
+    {
+        return self
+            .ranges
+            .iter()
+            .fold(T::zero(), |total, r| total + (r.end - r.start));
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> RangeSet<T> {
+        let mut set = RangeSet {
+            ranges: iter.into_iter().collect(),
+        };
+        set.normalize();
+        return set;
+    }
+}
+
+impl<T: Copy + Ord> From<Range<T>> for RangeSet<T> {
+    fn from(range: Range<T>) -> RangeSet<T> {
+        return [range].into_iter().collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[Range<i32>]) -> RangeSet<i32> {
+        ranges.iter().cloned().collect()
+    }
+
+    #[test]
+    fn normalizes() {
+        let s = set(&[5..7, 0..2, 1..3, 3..4, 8..8]);
+        assert_eq!(s.ranges(), &[0..4, 5..7]);
+        assert_eq!(s.len(), 6);
+        assert_eq!(s.min(), Some(0));
+        assert!(s.contains(3) && !s.contains(4));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[0..10, 20..30]);
+        let b = RangeSet::from(5..25);
+        assert_eq!(a.union(&b), RangeSet::from(0..30));
+        assert_eq!(a.intersection(&b), set(&[5..10, 20..25]));
+        assert_eq!(a.difference(&b), set(&[0..5, 25..30]));
+        assert_eq!(b.difference(&a), RangeSet::from(10..20));
+        assert_eq!(a.difference(&a), RangeSet::new());
+        assert_eq!(
+            RangeSet::from(0..30).difference(&set(&[2..3, 5..6])),
+            set(&[0..2, 3..5, 6..30])
+        );
+    }
+
+    #[test]
+    fn split_and_shift() {
+        let a = set(&[0..10, 20..30]);
+        assert_eq!(a.split_at(5), (RangeSet::from(0..5), set(&[5..10, 20..30])));
+        assert_eq!(
+            a.split_at(20),
+            (RangeSet::from(0..10), RangeSet::from(20..30))
+        );
+        assert_eq!(a.shift(-10), set(&[-10..0, 10..20]));
+    }
+}
@@ -0,0 +1,149 @@
+//! Shortest paths over weighted graphs given by a successor function, Dijkstra or A*.
+use num::Zero;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// How much work a search did.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    /// states taken off the queue and expanded
+    pub expanded: usize,
+    /// entries pushed onto the queue, including the starts
+    pub pushed: usize,
+}
+
+/// The cheapest way to a goal, `path` goes from a start to the goal (both included).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S, C> {
+    pub cost: C,
+    pub path: Vec<S>,
+    pub stats: Stats,
+}
+
+/// Dijkstra from `starts` to the first state that `is_goal`. `successors` lists the states
+/// reachable from a state, with the cost of getting there.
+pub fn dijkstra<S, C, N, I, G>(
+    starts: impl IntoIterator<Item = S>,
+    successors: N,
+    is_goal: G,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    G: FnMut(&S) -> bool,
+{
+    return astar(starts, successors, is_goal, |_| C::zero());
+}
+
+/// A* from `starts` to the first state that `is_goal`. `heuristic` estimates the remaining cost
+/// from a state, it must never overestimate it for the result to be the cheapest.
+pub fn astar<S, C, N, I, G, H>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: N,
+    mut is_goal: G,
+    mut heuristic: H,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    G: FnMut(&S) -> bool,
+    H: FnMut(&S) -> C,
+{
+    // states are numbered in the order they're found, the queue only holds the numbers
+    let mut states = Vec::<S>::new();
+    let mut numbers = HashMap::<S, usize>::new();
+    // the best known cost of every state and where it was reached from
+    let mut best = Vec::<(C, Option<usize>)>::new();
+    let mut queue = BinaryHeap::<Reverse<(C, C, usize)>>::new();
+    let mut stats = Stats::default();
+
+    for start in starts {
+        if numbers.contains_key(&start) {
+            continue;
+        }
+        let n = states.len();
+        queue.push(Reverse((heuristic(&start), C::zero(), n)));
+        numbers.insert(start.clone(), n);
+        states.push(start);
+        best.push((C::zero(), None));
+        stats.pushed += 1;
+    }
+
+    while let Some(Reverse((_, cost, n))) = queue.pop() {
+        if cost > best[n].0 {
+            continue; // a cheaper way was found after this one was queued
+        }
+        if is_goal(&states[n]) {
+            let mut path = vec![states[n].clone()];
+            let mut previous = best[n].1;
+            while let Some(p) = previous {
+                path.push(states[p].clone());
+                previous = best[p].1;
+            }
+            path.reverse();
+            return Some(Path { cost, path, stats });
+        }
+        stats.expanded += 1;
+
+        for (next, step_cost) in successors(&states[n]) {
+            let next_cost = cost + step_cost;
+            let m = match numbers.get(&next) {
+                Some(&m) if best[m].0 <= next_cost => continue,
+                Some(&m) => {
+                    best[m] = (next_cost, Some(n));
+                    m
+                }
+                None => {
+                    let m = states.len();
+                    numbers.insert(next.clone(), m);
+                    states.push(next);
+                    best.push((next_cost, Some(n)));
+                    m
+                }
+            };
+            queue.push(Reverse((next_cost + heuristic(&states[m]), next_cost, m)));
+            stats.pushed += 1;
+        }
+    }
+    return None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a -1-> b -1-> d, a -5-> c -1-> d, a -10-> d
+    fn successors(node: &char) -> Vec<(char, u32)> {
+        match node {
+            'a' => vec![('b', 1), ('c', 5), ('d', 10)],
+            'b' => vec![('d', 1)],
+            'c' => vec![('d', 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn dijkstra_finds_the_cheapest_path() {
+        let result = dijkstra(['a'], successors, |&n| n == 'd').unwrap();
+        assert_eq!(result.cost, 2);
+        assert_eq!(result.path, vec!['a', 'b', 'd']);
+        assert_eq!(dijkstra(['b'], successors, |&n| n == 'a'), None);
+    }
+
+    #[test]
+    fn astar_expands_less() {
+        // walking a line from 0 to 100, one step at a time in either direction
+        let steps = |&n: &i32| [(n - 1, 1), (n + 1, 1)];
+        let plain = dijkstra([0], steps, |&n| n == 100).unwrap();
+        let guided = astar([0], steps, |&n| n == 100, |&n| (100 - n).abs()).unwrap();
+        assert_eq!((plain.cost, guided.cost), (100, 100));
+        assert_eq!(guided.path, (0..=100).collect::<Vec<_>>());
+        assert!(guided.stats.expanded < plain.stats.expanded);
+    }
+}
@@ -0,0 +1,371 @@
+//! Points and vectors in 2D and 3D, with the usual operators and bounding boxes.
+use super::Direction;
+use num::Signed;
+use std::cmp::{max, min};
+use std::ops;
+
+/// A point (or vector) on a grid, `y` grows downwards. Coordinates are `i32` unless a wider
+/// integer is needed to not overflow, e.g. `Point2D<i64>`.
+#[derive(Hash, Eq, PartialEq, Debug, Copy, Clone, PartialOrd, Ord)]
+pub struct Point2D<T = i32> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Signed + Copy> Point2D<T> {
+    pub fn new(x: T, y: T) -> Point2D<T> {
+        Point2D { x, y }
+    }
+
+    pub fn zero() -> Point2D<T> {
+        Point2D::new(T::zero(), T::zero())
+    }
+
+    pub fn manhattan_len(self) -> T {
+        self.x.abs() + self.y.abs()
+    }
+
+    pub fn manhattan_normalize(self) -> (T, Point2D<T>) {
+        let len = self.manhattan_len();
+        (len, Point2D::new(self.x / len, self.y / len))
+    }
+
+    pub fn clockwise(self) -> Point2D<T> {
+        Point2D::new(-self.y, self.x)
+    }
+
+    pub fn counter_clockwise(self) -> Point2D<T> {
+        Point2D::new(self.y, -self.x)
+    }
+
+    pub fn translate(self, direction: Direction) -> Point2D<T> {
+        let (zero, one) = (T::zero(), T::one());
+        self + match direction {
+            Direction::Up => Point2D::new(zero, -one),
+            Direction::Right => Point2D::new(one, zero),
+            Direction::Down => Point2D::new(zero, one),
+            Direction::Left => Point2D::new(-one, zero),
+        }
+    }
+
+    pub fn dot(self, other: Point2D<T>) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// The z of the 3D cross product, positive when `other` is clockwise of `self` (`y` is down).
+    pub fn cross(self, other: Point2D<T>) -> T {
+        self.x * other.y - self.y * other.x
+    }
+}
+
+impl<T: Signed + Copy + Ord> Point2D<T> {
+    pub fn chebyshev_len(self) -> T {
+        max(self.x.abs(), self.y.abs())
+    }
+}
+
+impl<T> From<(T, T)> for Point2D<T> {
+    fn from(e: (T, T)) -> Point2D<T> {
+        return Point2D { x: e.0, y: e.1 };
+    }
+}
+
+/// A point (or vector) in space. Coordinates are `i64` unless given, unlike [`Point2D`]'s `i32`:
+/// the 3D puzzles have coordinates in the hundreds of thousands and multiply them.
+#[derive(Hash, Eq, PartialEq, Debug, Copy, Clone, PartialOrd, Ord)]
+pub struct Point3D<T = i64> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Signed + Copy> Point3D<T> {
+    pub fn new(x: T, y: T, z: T) -> Point3D<T> {
+        Point3D { x, y, z }
+    }
+
+    pub fn zero() -> Point3D<T> {
+        Point3D::new(T::zero(), T::zero(), T::zero())
+    }
+
+    pub fn manhattan_len(self) -> T {
+        self.x.abs() + self.y.abs() + self.z.abs()
+    }
+
+    pub fn dot(self, other: Point3D<T>) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(self, other: Point3D<T>) -> Point3D<T> {
+        Point3D::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+}
+
+impl<T: Signed + Copy + Ord> Point3D<T> {
+    pub fn chebyshev_len(self) -> T {
+        max(max(self.x.abs(), self.y.abs()), self.z.abs())
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3D<T> {
+    fn from(e: (T, T, T)) -> Point3D<T> {
+        return Point3D {
+            x: e.0,
+            y: e.1,
+            z: e.2,
+        };
+    }
+}
+
+// the operators for every combination of owned and borrowed operands
+macro_rules! point_op {
+    ($point:ident, $op:ident, $method:ident, |$a:ident, $b:ident: $rhs:ty| $body:expr) => {
+        impl<T: Signed + Copy> ops::$op<$rhs> for $point<T> {
+            type Output = $point<T>;
+            fn $method(self, $b: $rhs) -> $point<T> {
+                let $a = self;
+                $body
+            }
+        }
+        impl<T: Signed + Copy> ops::$op<&$rhs> for $point<T> {
+            type Output = $point<T>;
+            fn $method(self, $b: &$rhs) -> $point<T> {
+                let ($a, $b) = (self, *$b);
+                $body
+            }
+        }
+        impl<T: Signed + Copy> ops::$op<$rhs> for &$point<T> {
+            type Output = $point<T>;
+            fn $method(self, $b: $rhs) -> $point<T> {
+                let $a = *self;
+                $body
+            }
+        }
+        impl<T: Signed + Copy> ops::$op<&$rhs> for &$point<T> {
+            type Output = $point<T>;
+            fn $method(self, $b: &$rhs) -> $point<T> {
+                let ($a, $b) = (*self, *$b);
+                $body
+            }
+        }
+    };
+}
+
+point_op!(Point2D, Add, add, |a, b: Point2D<T>| Point2D::new(
+    a.x + b.x,
+    a.y + b.y
+));
+point_op!(Point2D, Sub, sub, |a, b: Point2D<T>| Point2D::new(
+    a.x - b.x,
+    a.y - b.y
+));
+point_op!(Point2D, Mul, mul, |a, b: T| Point2D::new(a.x * b, a.y * b));
+
+point_op!(Point3D, Add, add, |a, b: Point3D<T>| Point3D::new(
+    a.x + b.x,
+    a.y + b.y,
+    a.z + b.z
+));
+point_op!(Point3D, Sub, sub, |a, b: Point3D<T>| Point3D::new(
+    a.x - b.x,
+    a.y - b.y,
+    a.z - b.z
+));
+point_op!(Point3D, Mul, mul, |a, b: T| Point3D::new(
+    a.x * b,
+    a.y * b,
+    a.z * b
+));
+
+impl<T: Signed + Copy> ops::Neg for Point2D<T> {
+    type Output = Point2D<T>;
+
+    fn neg(self) -> Point2D<T> {
+        Point2D::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Signed + Copy> ops::Neg for Point3D<T> {
+    type Output = Point3D<T>;
+
+    fn neg(self) -> Point3D<T> {
+        Point3D::new(-self.x, -self.y, -self.z)
+    }
+}
+
+/// Component-wise comparisons, what [`Aabb`] needs of its corners.
+pub trait Vector: Copy {
+    /// the smallest of each component (`Ord::min` compares the points lexicographically)
+    fn component_min(self, other: Self) -> Self;
+    /// the largest of each component
+    fn component_max(self, other: Self) -> Self;
+    /// whether every component is less than or equal to the one of `other`
+    fn all_le(self, other: Self) -> bool;
+}
+
+impl<T: Ord + Copy> Vector for Point2D<T> {
+    fn component_min(self, other: Self) -> Self {
+        Point2D {
+            x: min(self.x, other.x),
+            y: min(self.y, other.y),
+        }
+    }
+
+    fn component_max(self, other: Self) -> Self {
+        Point2D {
+            x: max(self.x, other.x),
+            y: max(self.y, other.y),
+        }
+    }
+
+    fn all_le(self, other: Self) -> bool {
+        self.x <= other.x && self.y <= other.y
+    }
+}
+
+impl<T: Ord + Copy> Vector for Point3D<T> {
+    fn component_min(self, other: Self) -> Self {
+        Point3D {
+            x: min(self.x, other.x),
+            y: min(self.y, other.y),
+            z: min(self.z, other.z),
+        }
+    }
+
+    fn component_max(self, other: Self) -> Self {
+        Point3D {
+            x: max(self.x, other.x),
+            y: max(self.y, other.y),
+            z: max(self.z, other.z),
+        }
+    }
+
+    fn all_le(self, other: Self) -> bool {
+        self.x <= other.x && self.y <= other.y && self.z <= other.z
+    }
+}
+
+/// An axis-aligned bounding box, both corners are inside the box.
+#[derive(Hash, Eq, PartialEq, Debug, Copy, Clone)]
+pub struct Aabb<P> {
+    pub min: P,
+    pub max: P,
+}
+
+impl<P: Vector> Aabb<P> {
+    /// The box with the opposite corners `a` and `b`.
+    pub fn new(a: P, b: P) -> Aabb<P> {
+        Aabb {
+            min: a.component_min(b),
+            max: a.component_max(b),
+        }
+    }
+
+    /// The smallest box around all `points`, none if there aren't any.
+    pub fn around<I: IntoIterator<Item = P>>(points: I) -> Option<Aabb<P>> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        return Some(points.fold(Aabb::new(first, first), |bounds, p| {
+            bounds.union(&Aabb::new(p, p))
+        }));
+    }
+
+    pub fn contains(&self, point: P) -> bool {
+        self.min.all_le(point) && point.all_le(self.max)
+    }
+
+    pub fn intersects(&self, other: &Aabb<P>) -> bool {
+        self.min.all_le(other.max) && other.min.all_le(self.max)
+    }
+
+    /// The smallest box around both boxes.
+    pub fn union(&self, other: &Aabb<P>) -> Aabb<P> {
+        Aabb {
+            min: self.min.component_min(other.min),
+            max: self.max.component_max(other.max),
+        }
+    }
+
+    pub fn intersection(&self, other: &Aabb<P>) -> Option<Aabb<P>> {
+        if !self.intersects(other) {
+            return None;
+        }
+        return Some(Aabb {
+            min: self.min.component_max(other.min),
+            max: self.max.component_min(other.max),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point_operators() {
+        let a = Point2D::new(1, 2);
+        let b = Point2D::new(3, -4);
+        assert_eq!(a + b, Point2D::new(4, -2));
+        assert_eq!(a - b, Point2D::new(-2, 6));
+        assert_eq!(-(a * 2), Point2D::new(-2, -4));
+        assert_eq!(b.manhattan_len(), 7);
+        assert_eq!(a.clockwise().counter_clockwise(), a);
+        assert_eq!(a.translate(Direction::Up), Point2D::new(1, 1));
+    }
+
+    #[test]
+    fn wide_points() {
+        // 0x7ffff steps of 0x7ffff would overflow an i32
+        let far = Point2D::<i64>::zero().translate(Direction::Right) * 0x7ffff;
+        assert_eq!((far * 0x7ffff).manhattan_len(), 0x7ffff * 0x7ffff);
+    }
+
+    #[test]
+    fn vector_products() {
+        let a = Point2D::new(1, 0);
+        assert_eq!(a.dot(Point2D::new(3, 4)), 3);
+        assert!(a.cross(a.clockwise()) > 0);
+        assert_eq!(Point2D::new(3, -4).chebyshev_len(), 4);
+
+        let x = Point3D::new(1, 0, 0);
+        let y = Point3D::new(0, 1, 0);
+        assert_eq!(x.cross(y), Point3D::new(0, 0, 1));
+        assert_eq!(x.dot(y), 0);
+        assert_eq!(x - y * 2 + -x, Point3D::new(0, -2, 0));
+        let p = Point3D::new(1, -5, 3);
+        assert_eq!((p.manhattan_len(), p.chebyshev_len()), (9, 5));
+    }
+
+    #[test]
+    fn bounding_boxes() {
+        let points = [(1, 5, 0), (4, 2, 3), (2, 3, 9)].map(Point3D::from);
+        let bounds = Aabb::around(points).unwrap();
+        assert_eq!(
+            bounds,
+            Aabb::new(Point3D::new(4, 5, 9), Point3D::new(1, 2, 0))
+        );
+        assert!(points.iter().all(|&p| bounds.contains(p)));
+        assert!(!bounds.contains(Point3D::new(0, 3, 3)));
+
+        let other = Aabb::new(Point3D::new(3, 3, 3), Point3D::new(10, 10, 10));
+        let overlap = Aabb::new(Point3D::new(3, 3, 3), Point3D::new(4, 5, 9));
+        assert_eq!(bounds.intersection(&other), Some(overlap));
+        let apart = Aabb::new(Point3D::new(5, 0, 0), Point3D::new(6, 1, 1));
+        assert_eq!(bounds.intersection(&apart), None);
+        let all = Aabb::new(Point3D::new(1, 0, 0), Point3D::new(6, 5, 9));
+        assert_eq!(bounds.union(&apart), all);
+    }
+
+    #[test]
+    fn component_min_max() {
+        let (a, b) = (Point2D::new(1, 5), Point2D::new(2, 3));
+        assert_eq!(a.component_min(b), Point2D::new(1, 3));
+        assert_eq!(a.component_max(b), Point2D::new(2, 5));
+        // not the lexicographic ones
+        assert_eq!(a.min(b), a);
+    }
+}
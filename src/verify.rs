@@ -0,0 +1,156 @@
+//! Checks the solvers against the known answers in `answers.json`, for `aoc-2023 verify`.
+//!
+//! Every entry names a day, an input file and the expected answers of its parts, so a new input
+//! (and its answers) can be checked without touching any tests.
+use crate::answer::Answer;
+use crate::solution;
+use crate::utils;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+use std::path::Path;
+use thiserror::Error;
+
+/// The default location of the known answers, relative to the crate root.
+pub const KNOWN_ANSWERS: &str = "answers.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KnownAnswer {
+    pub day: u8,
+    /// relative to the crate root, or absolute
+    pub input: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part1: Option<Answer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part2: Option<Answer>,
+}
+
+#[derive(Debug, Error)]
+pub enum KnownAnswersError {
+    #[error(transparent)]
+    Input(#[from] utils::InputError),
+    #[error("invalid known answers: {0}")]
+    Format(#[from] serde_json::Error),
+}
+
+pub fn load_known_answers<P: AsRef<Path>>(path: P) -> Result<Vec<KnownAnswer>, KnownAnswersError> {
+    let text = utils::read_input(path)?;
+    return Ok(serde_json::from_str(&text)?);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail(Answer),
+    /// the input could not be read or parsed, or the day doesn't exist
+    Error(String),
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Pass => write!(f, "pass"),
+            Outcome::Fail(_) => write!(f, "FAIL"),
+            Outcome::Error(_) => write!(f, "ERROR"),
+        }
+    }
+}
+
+/// The result of checking a single part of a [`KnownAnswer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub expected: Answer,
+    pub outcome: Outcome,
+}
+
+/// Solves the parts with a known answer, the input of each entry is parsed once.
+pub fn verify(known: &KnownAnswer) -> Vec<Check> {
+    let parts: Vec<(u8, &Answer)> = [(1, &known.part1), (2, &known.part2)]
+        .into_iter()
+        .filter_map(|(part, expected)| expected.as_ref().map(|x| (part, x)))
+        .collect();
+    let check = |part: u8, expected: &Answer, outcome: Outcome| Check {
+        day: known.day,
+        part,
+        input: known.input.clone(),
+        expected: expected.clone(),
+        outcome,
+    };
+    let errors = |message: String| {
+        parts
+            .iter()
+            .map(|(part, expected)| check(*part, expected, Outcome::Error(message.clone())))
+            .collect::<Vec<Check>>()
+    };
+
+    let solver = match solution::solver(known.day) {
+        Some(x) => x,
+        None => return errors(format!("day {} is not implemented", known.day)),
+    };
+    // the inputs are listed relative to the crate root, like the committed ones
+    let input = match utils::read_input(utils::resolve(&known.input)) {
+        Ok(x) => x,
+        Err(e) => return errors(e.to_string()),
+    };
+    let prepared = match solver.prepare(&input) {
+        Ok(x) => x,
+        Err(e) => return errors(e.to_string()),
+    };
+    return parts
+        .iter()
+        .map(|(part, expected)| {
+            let actual = prepared.part(*part).expect("all days have two parts");
+            let outcome = match &actual == *expected {
+                true => Outcome::Pass,
+                false => Outcome::Fail(actual),
+            };
+            check(*part, expected, outcome)
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example(part1: i32, part2: i32) -> KnownAnswer {
+        KnownAnswer {
+            day: 5,
+            input: "src/day05.example.txt".to_string(),
+            part1: Some(part1.into()),
+            part2: Some(part2.into()),
+        }
+    }
+
+    #[test]
+    fn verify_example() {
+        let outcomes: Vec<Outcome> = verify(&example(35, 47))
+            .into_iter()
+            .map(|c| c.outcome)
+            .collect();
+        assert_eq!(outcomes, vec![Outcome::Pass, Outcome::Fail(46.into())]);
+    }
+
+    #[test]
+    fn verify_errors() {
+        let missing = KnownAnswer {
+            input: "src/day05.missing.txt".to_string(),
+            part2: None,
+            ..example(35, 46)
+        };
+        let checks = verify(&missing);
+        assert_eq!(checks.len(), 1);
+        assert!(matches!(checks[0].outcome, Outcome::Error(_)));
+    }
+
+    #[test]
+    fn known_answers_are_valid() {
+        let known = load_known_answers(utils::resolve(KNOWN_ANSWERS)).unwrap();
+        for entry in known {
+            assert!(solution::solver(entry.day).is_some(), "day {}", entry.day);
+            assert!(utils::resolve(&entry.input).exists(), "{}", entry.input);
+        }
+    }
+}